@@ -0,0 +1,8 @@
+# Seed data, loaded by `blue-eyes db seed` with DB connected to DATABASE_URL.
+# Create records through the models, for example:
+#
+#   User.create(full_name: "Admin", username: "admin", role: "admin")
+
+Dir.glob("./app/models/*.rb").each do |file|
+  require file
+end
@@ -20,7 +20,8 @@ module FormatHelpers
   end
 
   def respond
-    type_key = (request.accept[0].to_s == "application/json") ? :json : :html
+    json_requested = request.accept[0].to_s == "application/json"
+    type_key = (json_requested || @responses[:html].nil?) ? :json : :html
 
     case type_key
     when :json
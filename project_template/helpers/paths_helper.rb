@@ -19,7 +19,7 @@ module PathsHelper
         #if resource["as"].nil?
         #resources resource["name"].to_sym
           #else
-        resources resource["name"].to_sym, :as => (resource["as"] && resource["as"].size > 0 ? resource["as"].to_sym : nil), :belongs_to => (resource["belongs_to"] && resource["belongs_to"].size > 0 ? as_lookup[resource["belongs_to"]] : nil)
+        resources resource["name"].to_sym, :as => (resource["as"] && resource["as"].size > 0 ? resource["as"].to_sym : nil), :belongs_to => (resource["belongs_to"] && resource["belongs_to"].size > 0 ? as_lookup[resource["belongs_to"]] : nil), :namespace => (resource["namespace"] && resource["namespace"].size > 0 ? resource["namespace"] : nil)
           #end
       end
     end
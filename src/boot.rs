@@ -0,0 +1,24 @@
+use std::path::Path;
+use colored::Colorize;
+use tera::Context;
+use crate::dirs::Dir;
+use crate::template_writer::{write_template, WriteMode};
+
+// boot.rb loads the app without Rack, shared by config.ru, console and runner
+pub fn path() -> String {
+    Dir::Root(Some("boot.rb")).path()
+}
+
+pub fn write(mode: WriteMode) -> Result<bool, String> {
+    write_template(path(), "boot.template".to_string(), &Context::new(), mode)
+}
+
+// Projects generated before boot.rb existed get one, a customized boot.rb is left alone.
+pub fn ensure() -> Result<(), String> {
+    if Path::new(&path()).is_file() {
+        return Ok(());
+    }
+
+    println!("{}", "boot.rb not found, creating it".yellow());
+    write(WriteMode::Check).map(|_| ())
+}
@@ -1,8 +1,8 @@
 use std::process::{Command, Stdio};
-use std::{thread, vec};
-use std::io::{BufRead, BufReader};
-use thread::spawn;
+use std::vec;
 use colored::Colorize;
+use crate::database::Database;
+use crate::utils::stream::forward;
 
 pub struct Bundler<'a> {
     pub gems: Vec<&'a str>
@@ -28,8 +28,21 @@ impl<'a> Bundler<'a> {
 
         Bundler { gems }
     }
-    pub fn install(&self, db: &str) -> Result<(), String> {
-        let cmd = self.build_command(db);
+
+    pub fn gems_for(&self, db: Database) -> Vec<&'a str> {
+        let mut gems = self.gems.clone();
+        gems.push(db.gem());
+
+        gems
+    }
+
+    pub fn install(&self) -> Result<(), String> {
+        if self.is_installed() {
+            println!("{}", "Gems already installed".blue());
+            return Ok(());
+        }
+
+        let cmd = self.build_command();
 
         match self.run_install(cmd) {
             Ok(_) => Ok(()),
@@ -37,18 +50,17 @@ impl<'a> Bundler<'a> {
         }
     }
 
-    fn build_command(&self, db: &str) -> Command {
-        let mut cmd = Command::new("bundle");
-        cmd.arg("add");
-        for gem in &self.gems {
-            cmd.arg(gem);
-        }
+    fn is_installed(&self) -> bool {
+        Command::new("bundle")
+            .arg("check")
+            .output()
+            .map(|output| output.status.success())
+            .unwrap_or(false)
+    }
 
-        if db == "postgres" {
-            cmd.arg("pg");
-        } else {
-            cmd.arg("sqlite3");
-        }
+    fn build_command(&self) -> Command {
+        let mut cmd = Command::new("bundle");
+        cmd.arg("install");
         cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
 
         cmd
@@ -58,27 +70,11 @@ impl<'a> Bundler<'a> {
         let mut child = cmd.spawn().map_err(|err| err.to_string())?;
 
         if let Some(stdout) = child.stdout.take() {
-            let reader = BufReader::new(stdout);
-            spawn(move || {
-                for line in reader.lines() {
-                    match line {
-                        Ok(line) => println!("{}", line.blue()),
-                        Err(e)  => println!("Error: {}", e.to_string().red())
-                    }
-                }
-            });
+            forward(stdout, |line| println!("{}", line.blue()));
         };
 
         if let Some(stderr) = child.stderr.take() {
-            let reader = BufReader::new(stderr);
-            spawn(move || {
-                for line in reader.lines() {
-                    match line {
-                        Ok(line) => eprintln!("{}", line),
-                        Err(e)  => println!("Error: {}", e)
-                    }
-                }
-            });
+            forward(stderr, |line| eprintln!("{}", line));
         };
 
         let status = child.wait().map_err(|err| err.to_string())?;
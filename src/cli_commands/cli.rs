@@ -1,4 +1,7 @@
+use crate::database::Database;
+use crate::environment::Environment;
 use clap::{Args, Parser, Subcommand};
+use crate::template_writer::WriteMode;
 
 #[allow(dead_code)]
 #[derive(Debug, Clone)]
@@ -9,6 +12,7 @@ pub enum CommandType {
     Model,
     Migrate,
     Scaffold,
+    Service,
 }
 
 #[derive(Parser, Debug)]
@@ -17,19 +21,57 @@ pub enum CommandType {
     about = "A cli_commands to generate projects and files for Ruby, Sinatra, Sequel and Haml/Tailwind"
 )]
 pub struct Cli {
+    // loads .env.<env> before .env, defaults to APP_ENV or development
+    #[arg(long, global = true, value_enum)]
+    pub(crate) env: Option<Environment>,
+
     #[command(subcommand)]
     pub(crate) command: Commands,
 }
 
 #[derive(Parser, Debug)]
 pub enum Commands {
-    New {
-        project_name: String,
+    New(NewArgs),
+    Setup(SetupArgs),
+    // --to applies to `migrate` itself, not to its subcommands
+    #[command(args_conflicts_with_subcommands = true)]
+    Migrate {
+        #[arg(long)]
+        to: Option<i64>,
 
-        #[arg(long, default_value = "sqlite")]
-        db: String,
+        #[command(subcommand)]
+        action: Option<MigrateSubcommand>,
+    },
+    Db {
+        #[command(subcommand)]
+        action: DbSubcommand,
+    },
+    Routes {
+        #[arg(long)]
+        json: bool,
+    },
+    // rackup and the tailwind watcher with prefixed output, Ctrl-C stops both
+    #[command(visible_alias = "dev")]
+    Server {
+        #[arg(long, short, default_value_t = 9292)]
+        port: u16,
+
+        #[arg(long, default_value = "localhost")]
+        host: String,
+    },
+    Runner {
+        // a ruby file, or a snippet such as 'puts Post.count'
+        code: String,
+
+        // passed to the script as ARGV
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    Console {
+        // wrap the session in a transaction that is rolled back on exit
+        #[arg(long)]
+        sandbox: bool,
     },
-    Migrate,
     Generate {
         #[command(subcommand)]
         entity: GenerateSubcommand,
@@ -38,6 +80,60 @@ pub enum Commands {
         #[command(subcommand)]
         entity: GenerateSubcommand,
     },
+    Destroy {
+        #[command(subcommand)]
+        entity: DestroySubcommand,
+    },
+    Templates {
+        #[command(subcommand)]
+        action: TemplatesSubcommand,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum TemplatesSubcommand {
+    Eject {
+        name: Option<String>,
+
+        #[arg(long)]
+        user: bool,
+
+        #[command(flatten)]
+        write: WriteArgs,
+    },
+    List,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum DestroySubcommand {
+    Controller(DestroyArgs),
+    Model(DestroyArgs),
+    Api(DestroyArgs),
+    Scaffold(DestroyArgs),
+    Service(DestroyArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct DestroyArgs {
+    pub name: String,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum MigrateSubcommand {
+    Rollback {
+        #[arg(long, default_value_t = 1)]
+        steps: usize,
+    },
+    Redo,
+    Status,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum DbSubcommand {
+    Create,
+    Drop,
+    Reset,
+    Seed,
 }
 
 #[derive(Subcommand, Debug)]
@@ -46,6 +142,7 @@ pub enum GenerateSubcommand {
     Model(SharedArgs),
     Api(SharedArgs),
     Scaffold(SharedArgs),
+    Service(ServiceArgs),
     //Migration(MigrationArgs),
     Migration {
         #[command(subcommand)]
@@ -53,20 +150,41 @@ pub enum GenerateSubcommand {
     },
 }
 
+impl GenerateSubcommand {
+    pub fn write_mode(&self) -> WriteMode {
+        match self {
+            GenerateSubcommand::Controller(args)
+            | GenerateSubcommand::Model(args)
+            | GenerateSubcommand::Api(args)
+            | GenerateSubcommand::Scaffold(args) => args.write.mode(),
+            GenerateSubcommand::Service(args) => args.write.mode(),
+            GenerateSubcommand::Migration { entity } => match entity {
+                MigrationSubcommand::Alter { write, .. } | MigrationSubcommand::Drop { write, .. } => write.mode(),
+            },
+        }
+    }
+}
+
 #[derive(Subcommand, Debug)]
 pub enum MigrationSubcommand {
     Alter {
         table_name: String,
 
-        #[arg(long)]
-        add: Option<String>,
+        #[arg(long, value_delimiter = ' ')]
+        add: Option<Vec<String>>,
 
-        #[arg(long)]
-        drop: Option<String>,
+        #[arg(long, value_delimiter = ' ')]
+        drop: Option<Vec<String>>,
+
+        #[command(flatten)]
+        write: WriteArgs,
     },
 
     Drop {
-        table_name: String
+        table_name: String,
+
+        #[command(flatten)]
+        write: WriteArgs,
     }
 }
 
@@ -80,6 +198,42 @@ pub struct AlterArgs {
     drop: Option<String>,
 }
 
+#[derive(Args, Debug)]
+pub struct NewArgs {
+    pub project_name: String,
+
+    #[arg(long, value_enum, default_value_t)]
+    pub db: Database,
+
+    #[arg(long, default_value = crate::tailwind::DEFAULT_VERSION)]
+    pub tailwind_version: String,
+
+    #[arg(long)]
+    pub skip_bundle: bool,
+
+    #[arg(long)]
+    pub skip_migrate: bool,
+
+    #[arg(long)]
+    pub skip_tailwind: bool,
+
+    #[arg(long)]
+    pub skip_git: bool,
+
+    #[arg(long)]
+    pub offline: bool,
+
+    #[command(flatten)]
+    pub write: WriteArgs,
+}
+
+#[derive(Args, Debug)]
+pub struct SetupArgs {
+    // defaults to the version recorded in blue_eyes.toml
+    #[arg(long)]
+    pub tailwind_version: Option<String>,
+}
+
 #[derive(Args, Debug)]
 pub struct SharedArgs {
     pub name: String,
@@ -92,4 +246,51 @@ pub struct SharedArgs {
 
     #[arg(long)]
     pub belongs_to: Option<String>,
+
+    #[command(flatten)]
+    pub write: WriteArgs,
+}
+
+#[derive(Args, Debug)]
+pub struct ServiceArgs {
+    pub name: String,
+
+    // defaults to index show create update destroy, other names get an empty method
+    pub actions: Vec<String>,
+
+    // a controller that delegates to the service, with views
+    #[arg(long)]
+    pub with_controller: bool,
+
+    #[arg(long, value_delimiter = ' ', requires = "with_controller")]
+    pub fields: Option<Vec<String>>,
+
+    #[arg(long, requires = "with_controller")]
+    pub alias: Option<String>,
+
+    #[command(flatten)]
+    pub write: WriteArgs,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct WriteArgs {
+    #[arg(long, conflicts_with_all = ["skip", "pretend"])]
+    pub force: bool,
+
+    #[arg(long, conflicts_with = "pretend")]
+    pub skip: bool,
+
+    #[arg(long)]
+    pub pretend: bool,
+}
+
+impl WriteArgs {
+    pub fn mode(&self) -> WriteMode {
+        match (self.force, self.skip, self.pretend) {
+            (true, _, _) => WriteMode::Force,
+            (_, true, _) => WriteMode::Skip,
+            (_, _, true) => WriteMode::Pretend,
+            _ => WriteMode::Check,
+        }
+    }
 }
@@ -0,0 +1,349 @@
+use crate::inflections::pluralize;
+use serde::Serialize;
+
+const FIELD_TYPES: &str = "String, Text, Integer, Boolean, Date, DateTime, Decimal, Float, references";
+
+// Sequel creates String columns as varchar(255)
+const STRING_SIZE: u32 = 255;
+
+// format=<name> and the ruby pattern validate_format checks against
+const FORMATS: [(&str, &str); 3] = [
+    ("email", "URI::MailTo::EMAIL_REGEXP"),
+    ("url", r"/\Ahttps?:\/\/\S+\z/"),
+    ("slug", r"/\A[a-z0-9]+(?:-[a-z0-9]+)*\z/"),
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum FieldType {
+    String,
+    Text,
+    Integer,
+    Boolean,
+    Date,
+    DateTime,
+    Decimal,
+    Float,
+    References,
+}
+
+impl FieldType {
+    fn parse(sql_type: &str) -> Option<Self> {
+        match sql_type.to_lowercase().as_str() {
+            "string" => Some(FieldType::String),
+            "text" => Some(FieldType::Text),
+            "integer" => Some(FieldType::Integer),
+            "boolean" => Some(FieldType::Boolean),
+            "date" => Some(FieldType::Date),
+            "datetime" => Some(FieldType::DateTime),
+            "decimal" => Some(FieldType::Decimal),
+            "float" => Some(FieldType::Float),
+            "references" => Some(FieldType::References),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Field {
+    pub name: String,
+    pub column: String,
+    pub sql_type: FieldType,
+    pub table: Option<String>,
+    pub null: bool,
+    pub default: Option<String>,
+    pub unique: bool,
+    pub index: bool,
+    pub required: bool,
+    pub format: Option<String>,
+    pub max_length: Option<u32>,
+    // only set when max= differs from the varchar(255) Sequel creates
+    pub size: Option<u32>,
+}
+
+impl Field {
+    pub fn parse(field: &str) -> Result<Self, String> {
+        let mut parts = field.split(':');
+        let name = parts.next().unwrap_or_default();
+        let sql_type = match parts.next() {
+            Some(sql_type) if !sql_type.is_empty() => sql_type,
+            _ => return Err(format!("Invalid field '{}': expected name:Type", field)),
+        };
+
+        if FieldType::parse(name).is_some() && FieldType::parse(sql_type).is_none() {
+            return Err(format!(
+                "Invalid field '{}': fields are written as name:Type, did you mean '{}:{}'?",
+                field, sql_type, name
+            ));
+        }
+
+        if !Field::is_identifier(name) {
+            return Err(format!(
+                "Invalid field name '{}' in '{}': use lowercase letters, numbers and underscores",
+                name, field
+            ));
+        }
+
+        let sql_type = match FieldType::parse(sql_type) {
+            Some(sql_type) => sql_type,
+            None => {
+                return Err(format!(
+                    "Unknown type '{}' for field '{}'. Expected one of: {}",
+                    sql_type, name, FIELD_TYPES
+                ))
+            }
+        };
+
+        let is_reference = sql_type == FieldType::References;
+        let (column, table) = match is_reference {
+            true => (name.to_string() + "_id", Some(pluralize(name))),
+            false => (name.to_string(), None),
+        };
+
+        let mut parsed = Field {
+            name: name.to_string(),
+            column,
+            sql_type,
+            table,
+            null: true,
+            default: None,
+            unique: false,
+            index: is_reference,
+            required: false,
+            format: None,
+            max_length: None,
+            size: None,
+        };
+
+        for modifier in parts {
+            parsed.apply_modifier(modifier)?;
+        }
+
+        // longer values would fail in the database instead of the model
+        if parsed.sql_type == FieldType::String && parsed.max_length.is_none() {
+            parsed.max_length = Some(STRING_SIZE);
+        }
+
+        Ok(parsed)
+    }
+
+    pub fn parse_list(fields: &[String]) -> Result<Vec<Self>, String> {
+        fields.iter().map(|field| Field::parse(field)).collect()
+    }
+
+    fn apply_modifier(&mut self, modifier: &str) -> Result<(), String> {
+        let (key, value) = match modifier.split_once('=') {
+            Some((key, value)) => (key, Some(value)),
+            None => (modifier, None),
+        };
+
+        match (key, value) {
+            ("null", Some("false")) => self.null = false,
+            ("null", Some("true")) => self.null = true,
+            ("default", Some(value)) => self.default = Some(self.default_literal(value)?),
+            ("unique", None) => self.unique = true,
+            ("index", None) => self.index = true,
+            ("required", None) => self.required = true,
+            ("format", Some(value)) => self.format = Some(self.format_pattern(value)?),
+            ("max", Some(value)) => self.set_max_length(value)?,
+            _ => {
+                return Err(format!(
+                    "Unknown modifier '{}' for field '{}'. Expected null=true|false, default=<value>, unique, index, required, format=<format> or max=<length>",
+                    modifier, self.name
+                ))
+            }
+        }
+
+        Ok(())
+    }
+
+    fn format_pattern(&self, format: &str) -> Result<String, String> {
+        if self.sql_type != FieldType::String && self.sql_type != FieldType::Text {
+            return Err(format!("format={} needs a String or Text field, '{}' is {:?}", format, self.name, self.sql_type));
+        }
+
+        match FORMATS.iter().find(|(name, _)| *name == format) {
+            Some((_, pattern)) => Ok(pattern.to_string()),
+            None => {
+                let names: Vec<&str> = FORMATS.iter().map(|(name, _)| *name).collect();
+                Err(format!(
+                    "Unknown format '{}' for field '{}'. Expected one of: {}",
+                    format,
+                    self.name,
+                    names.join(", ")
+                ))
+            }
+        }
+    }
+
+    fn set_max_length(&mut self, value: &str) -> Result<(), String> {
+        if self.sql_type != FieldType::String && self.sql_type != FieldType::Text {
+            return Err(format!("max={} needs a String or Text field, '{}' is {:?}", value, self.name, self.sql_type));
+        }
+
+        let max_length = match value.parse::<u32>() {
+            Ok(max_length) if max_length > 0 => max_length,
+            _ => return Err(format!("Invalid max '{}' for field '{}': expected a positive number", value, self.name)),
+        };
+
+        self.max_length = Some(max_length);
+        if self.sql_type == FieldType::String && max_length != STRING_SIZE {
+            self.size = Some(max_length);
+        }
+
+        Ok(())
+    }
+
+    fn default_literal(&self, value: &str) -> Result<String, String> {
+        let valid = match self.sql_type {
+            FieldType::Integer | FieldType::References => value.parse::<i64>().is_ok(),
+            FieldType::Decimal | FieldType::Float => value.parse::<f64>().is_ok(),
+            FieldType::Boolean => value == "true" || value == "false",
+            _ => true,
+        };
+
+        if !valid {
+            return Err(format!(
+                "Invalid default '{}' for {:?} field '{}'",
+                value, self.sql_type, self.name
+            ));
+        }
+
+        match self.sql_type {
+            FieldType::String | FieldType::Text | FieldType::Date | FieldType::DateTime => {
+                Ok(format!("{:?}", value))
+            }
+            _ => Ok(value.to_string()),
+        }
+    }
+
+    fn is_identifier(name: &str) -> bool {
+        let mut chars = name.chars();
+        match chars.next() {
+            Some(c) if c.is_ascii_lowercase() || c == '_' => {}
+            _ => return false,
+        }
+
+        chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_name_and_type() {
+        let field = Field::parse("title:String").unwrap();
+
+        assert_eq!(field.name, "title");
+        assert_eq!(field.column, "title");
+        assert_eq!(field.sql_type, FieldType::String);
+        assert!(field.null);
+        assert!(!field.index);
+    }
+
+    #[test]
+    fn type_is_case_insensitive() {
+        assert_eq!(Field::parse("published_on:date").unwrap().sql_type, FieldType::Date);
+        assert_eq!(Field::parse("published_at:DATETIME").unwrap().sql_type, FieldType::DateTime);
+    }
+
+    #[test]
+    fn references_become_indexed_foreign_keys() {
+        let field = Field::parse("author:references").unwrap();
+
+        assert_eq!(field.column, "author_id");
+        assert_eq!(field.table, Some("authors".to_string()));
+        assert!(field.index);
+    }
+
+    #[test]
+    fn applies_column_modifiers() {
+        let field = Field::parse("views:Integer:null=false:default=0:unique:index").unwrap();
+
+        assert!(!field.null);
+        assert_eq!(field.default, Some("0".to_string()));
+        assert!(field.unique);
+        assert!(field.index);
+    }
+
+    #[test]
+    fn quotes_string_defaults() {
+        let field = Field::parse("status:String:default=draft").unwrap();
+
+        assert_eq!(field.default, Some("\"draft\"".to_string()));
+    }
+
+    #[test]
+    fn rejects_missing_type() {
+        assert!(Field::parse("title").is_err());
+        assert!(Field::parse("title:").is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_type() {
+        let err = Field::parse("title:Varchar").unwrap_err();
+
+        assert!(err.contains("Unknown type 'Varchar'"));
+    }
+
+    #[test]
+    fn suggests_swapping_type_and_name() {
+        let err = Field::parse("String:title").unwrap_err();
+
+        assert!(err.contains("did you mean 'title:String'"));
+    }
+
+    #[test]
+    fn rejects_invalid_names() {
+        assert!(Field::parse("Title:String").is_err());
+        assert!(Field::parse("1title:String").is_err());
+        assert!(Field::parse("sub-title:String").is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_modifiers_and_invalid_defaults() {
+        assert!(Field::parse("title:String:primary").is_err());
+        assert!(Field::parse("title:String:null=maybe").is_err());
+        assert!(Field::parse("views:Integer:default=many").is_err());
+        assert!(Field::parse("draft:Boolean:default=yes").is_err());
+    }
+
+    #[test]
+    fn applies_validation_modifiers() {
+        let field = Field::parse("email:String:required:format=email:max=80").unwrap();
+
+        assert!(field.required);
+        assert_eq!(field.format, Some("URI::MailTo::EMAIL_REGEXP".to_string()));
+        assert_eq!(field.max_length, Some(80));
+        assert_eq!(field.size, Some(80));
+    }
+
+    #[test]
+    fn limits_strings_to_the_column_size() {
+        let field = Field::parse("title:String").unwrap();
+
+        assert_eq!(field.max_length, Some(255));
+        assert_eq!(field.size, None);
+        assert_eq!(Field::parse("title:String:max=255").unwrap().size, None);
+        assert_eq!(Field::parse("body:Text").unwrap().max_length, None);
+    }
+
+    #[test]
+    fn text_max_does_not_size_the_column() {
+        let field = Field::parse("body:Text:max=5000").unwrap();
+
+        assert_eq!(field.max_length, Some(5000));
+        assert_eq!(field.size, None);
+    }
+
+    #[test]
+    fn rejects_invalid_validation_modifiers() {
+        assert!(Field::parse("email:String:format=phone").is_err());
+        assert!(Field::parse("views:Integer:format=slug").is_err());
+        assert!(Field::parse("views:Integer:max=10").is_err());
+        assert!(Field::parse("title:String:max=0").is_err());
+        assert!(Field::parse("title:String:max=long").is_err());
+        assert!(Field::parse("title:String:required=true").is_err());
+    }
+}
@@ -0,0 +1,73 @@
+use crate::cli_commands::cli::MigrationSubcommand;
+use crate::cli_commands::field::Field;
+use crate::dirs::Dir;
+use crate::template_writer::{write_template, WriteMode};
+use chrono::Utc;
+use crate::inflections::pluralize;
+use inflector::Inflector;
+use tera::Context;
+
+#[derive(Debug, Clone)]
+pub enum MigrationType {
+    Alter,
+    Drop,
+}
+
+#[derive(Debug, Clone)]
+pub struct Migration {
+    table_name: String,
+    add: Vec<Field>,
+    drop: Vec<Field>,
+    for_command: MigrationType,
+    write_mode: WriteMode,
+}
+
+impl Migration {
+    pub fn new(entity: &MigrationSubcommand) -> Result<Self, String> {
+        let migration = match entity {
+            MigrationSubcommand::Alter { table_name, add, drop, write } => Migration {
+                table_name: pluralize(&table_name.to_snake_case()),
+                add: Field::parse_list(add.as_deref().unwrap_or_default())?,
+                drop: Field::parse_list(drop.as_deref().unwrap_or_default())?,
+                for_command: MigrationType::Alter,
+                write_mode: write.mode(),
+            },
+            MigrationSubcommand::Drop { table_name, write } => Migration {
+                table_name: pluralize(&table_name.to_snake_case()),
+                add: vec![],
+                drop: vec![],
+                for_command: MigrationType::Drop,
+                write_mode: write.mode(),
+            },
+        };
+
+        Ok(migration)
+    }
+
+    pub fn generate_template(&self) -> Result<(), String> {
+        println!("Generating migration... {:?}", &self.for_command);
+        let (action, template_path) = match &self.for_command {
+            MigrationType::Alter => {
+                if self.add.is_empty() && self.drop.is_empty() {
+                    return Err("No columns to add or drop".to_string());
+                }
+                ("alter", "alter_table.template")
+            }
+            MigrationType::Drop => ("drop", "drop_table.template"),
+        };
+
+        let timestamp = Utc::now().timestamp();
+        let filename = timestamp.to_string() + "_" + action + "_" + &self.table_name + ".rb";
+        let output_path = Dir::Migrations(Some(filename.as_str())).path();
+
+        let mut context = Context::new();
+        context.insert("table_name", &self.table_name);
+        context.insert("add", &self.add);
+        context.insert("drop", &self.drop);
+
+        match write_template(output_path, template_path.to_string(), &context, self.write_mode) {
+            Ok(_) => Ok(()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+}
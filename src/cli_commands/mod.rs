@@ -1,3 +1,7 @@
 pub mod project;
 pub mod resource;
-pub mod cli;
\ No newline at end of file
+pub mod cli;
+pub mod field;
+pub mod migration;
+pub mod templates;
+pub mod routes;
\ No newline at end of file
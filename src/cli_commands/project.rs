@@ -1,20 +1,27 @@
 use crate::bundle::Bundler;
+use crate::database::Database;
 use crate::dirs::Dir;
-use crate::utils::fget::download_file;
+use crate::environment::{self, Environment};
+use crate::tailwind::Tailwind;
 use colored::Colorize;
 use rust_embed::RustEmbed;
 use std::env::{self, current_dir};
-use std::fs::{create_dir_all, File};
-use std::io::Write;
-use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::fs::{self, create_dir_all, remove_dir_all};
+use std::path::Path;
+use std::process::{self, Command};
 use rand::RngCore;
 use rand::rngs::OsRng;
 use tera::Context;
-use crate::template_writer::write_template;
+use crate::template_writer::{write_file, write_template, WriteMode};
 use base64::engine::Engine as _;
 use base64::engine::general_purpose::STANDARD as BASE64;
+use crate::inflections::Inflections;
+use crate::manifest::Manifest;
+use crate::boot;
+use crate::db;
 use crate::migrate;
+use crate::cli_commands::cli::{NewArgs, SetupArgs};
+use crate::utils::fcopy::copy_dir;
 
 #[derive(RustEmbed)]
 #[folder = "project_template"]
@@ -22,57 +29,81 @@ struct Templates;
 #[derive(Clone)]
 pub struct Project {
     name: String,
-    db: String,
+    db: Database,
     connection_string: Option<String>,
+    tailwind_version: String,
+    write_mode: WriteMode,
+    skip_bundle: bool,
+    skip_migrate: bool,
+    skip_tailwind: bool,
+    skip_git: bool,
+    offline: bool,
 }
 
 impl Project {
-    pub fn new(name: String, db: String) -> Self {
+    pub fn new(args: &NewArgs) -> Self {
         Project {
-            name,
-            db,
+            name: args.project_name.clone(),
+            db: args.db,
             connection_string: None,
+            tailwind_version: args.tailwind_version.clone(),
+            write_mode: args.write.mode(),
+            skip_bundle: args.skip_bundle,
+            skip_migrate: args.skip_migrate,
+            skip_tailwind: args.skip_tailwind,
+            skip_git: args.skip_git,
+            offline: args.offline,
         }
     }
 
-    pub fn generate(&mut self) -> Result<(), String> {
-        let current_dir = current_dir().unwrap();
-        let template_dir = current_dir.join(self.name.clone());
-
-        // copy all files/folders from project_template
-        self.copy_project_template(template_dir)?;
-        self.cd_app_dir()?;
-
-        // create .env file with db
-        self.connection_string = match self.create_env_file() {
-            Ok(connection_string) => Some(connection_string),
-            Err(e) => return Err(e.to_string()),
+    pub fn for_setup(args: &SetupArgs, manifest: &Manifest) -> Result<Self, String> {
+        let tailwind_version = match &args.tailwind_version {
+            Some(tailwind_version) => tailwind_version.clone(),
+            None => manifest.project.tailwind_version.clone(),
         };
 
-        // add bundle config to control bundler settings
-        self.create_bundle_config()?;
-
-        // add empty gemfile, except for required fields
-        self.create_gemfile()?;
+        Ok(Project {
+            name: manifest.project.name.clone(),
+            db: manifest.project.db,
+            connection_string: None,
+            tailwind_version,
+            write_mode: WriteMode::Check,
+            skip_bundle: false,
+            skip_migrate: false,
+            skip_tailwind: false,
+            skip_git: false,
+            offline: false,
+        })
+    }
 
-        // base config.ru for template
-        self.create_config_ru()?;
+    // The project is built in a staging directory next to the target and only moved into
+    // place once every step succeeded, a failed step leaves the working directory untouched.
+    pub fn generate(&mut self) -> Result<(), String> {
+        let original_dir = current_dir().map_err(|e| e.to_string())?;
+        let target_dir = original_dir.join(&self.name);
+        self.check_target_dir(&target_dir)?;
 
-        // add default gems and install
-        self.run_bundle(self.db.clone())?;
+        let staging_dir = original_dir.join(format!(".{}.blue_eyes-{}", self.name, process::id()));
+        let result = self.stage(&target_dir, &staging_dir);
 
-        // bin/dev loses execute, add back
-        self.chmod_x(Dir::Bin(Some("dev")).path())?;
+        // leave the staging directory before it is moved or removed
+        if let Err(e) = env::set_current_dir(&original_dir) {
+            return Err(e.to_string());
+        }
 
-        // run initial migrate, required for user model
-        self.run_migrate()?;
+        if let Err(e) = result {
+            self.remove_staging(&staging_dir);
+            println!("{} {}", "Rolled back".red(), target_dir.to_string_lossy().red().bold());
+            return Err(e);
+        }
 
-        // download tailwind
-        // mac version currently
-        self.download_tailwind()?;
+        // a dry run never touches the target directory
+        if self.write_mode == WriteMode::Pretend {
+            self.remove_staging(&staging_dir);
+            return Ok(());
+        }
 
-        // run init, make executable
-        self.init_tailwind()?;
+        self.move_into_place(&staging_dir, &target_dir)?;
 
         println!("\n{}", "Run app".blue());
         println!("-------------");
@@ -85,42 +116,242 @@ impl Project {
         Ok(())
     }
 
-    fn download_tailwind(&self) -> Result<(), String> {
-        println!("{}", "Downloading tailwind".blue());
-        let url = "https://github.com/tailwindlabs/tailwindcss/releases/latest/download/tailwindcss-macos-arm64";
-        if let Err(e) = download_file(url, &Dir::Bin(Some("tailwindcss")).path()) {
+    fn check_target_dir(&self, target_dir: &Path) -> Result<(), String> {
+        if !target_dir.exists() {
+            return Ok(());
+        }
+
+        if !target_dir.is_dir() {
+            return Err(target_dir.to_string_lossy().to_string() + " already exists and is not a directory");
+        }
+
+        let is_empty = match fs::read_dir(target_dir) {
+            Ok(mut entries) => entries.next().is_none(),
+            Err(e) => return Err(e.to_string()),
+        };
+
+        // --skip and --pretend would still merge into someone else's files, only --force opts in
+        if !is_empty && self.write_mode != WriteMode::Force {
+            return Err(target_dir.to_string_lossy().to_string() + " is not empty, use --force to generate into it");
+        }
+
+        Ok(())
+    }
+
+    fn stage(&mut self, target_dir: &Path, staging_dir: &Path) -> Result<(), String> {
+        // start from the existing files so conflicts are reported against them
+        if target_dir.exists() {
+            step("copy existing project", copy_dir(target_dir, staging_dir))?;
+        }
+
+        if let Err(e) = create_dir_all(staging_dir) {
             return Err(e.to_string());
         }
+        if let Err(e) = env::set_current_dir(staging_dir) {
+            return Err(e.to_string());
+        }
+
+        // copy all files/folders from project_template
+        step("copy project template", self.copy_project_template())?;
 
-        if let Err(e) = self.chmod_x(Dir::Bin(Some("tailwindcss")).path()) {
+        // manifest marks the project root for every other command
+        step("create manifest", self.create_manifest())?;
+
+        // inflection rules shared by the generator and the app, empty until customized
+        step("create inflections", Inflections::default().export(self.write_mode).map(|_| ()))?;
+
+        // create .env file with db
+        self.connection_string = Some(step("create .env", self.create_env_file())?);
+
+        // add bundle config to control bundler settings
+        step("create bundle config", self.create_bundle_config())?;
+
+        // gemfile with the default gems and the db adapter
+        step("create Gemfile", self.create_gemfile())?;
+
+        // base config.ru for template
+        step("create config.ru", self.create_config_ru())?;
+
+        // loads the app for config.ru, console and runner
+        step("create boot.rb", boot::write(self.write_mode).map(|_| ()))?;
+
+        // tailwind config, used once the binary is installed
+        step("create tailwind config", self.create_tailwind_config())?;
+
+        // a dry run stops before anything is installed or executed
+        if self.write_mode == WriteMode::Pretend {
+            return Ok(());
+        }
+
+        self.setup()
+    }
+
+    fn move_into_place(&self, staging_dir: &Path, target_dir: &Path) -> Result<(), String> {
+        if !target_dir.exists() {
+            return match fs::rename(staging_dir, target_dir) {
+                Ok(_) => Ok(()),
+                Err(e) => {
+                    self.remove_staging(staging_dir);
+                    Err(e.to_string())
+                }
+            };
+        }
+
+        // swap the directories, restoring the original if the staged copy can't be moved
+        let backup_dir = staging_dir.with_extension("old");
+        if let Err(e) = fs::rename(target_dir, &backup_dir) {
+            self.remove_staging(staging_dir);
             return Err(e.to_string());
         }
 
+        if let Err(e) = fs::rename(staging_dir, target_dir) {
+            let _ = fs::rename(&backup_dir, target_dir);
+            self.remove_staging(staging_dir);
+            return Err(e.to_string());
+        }
+
+        self.remove_staging(&backup_dir);
         Ok(())
     }
 
-    fn init_tailwind(&self) -> Result<(), String> {
-        let cmd = Command::new(Dir::Bin(Some("tailwindcss")).path())
-            .arg("init")
-            .output()
-            .map_err(|e| e.to_string())?;
+    fn remove_staging(&self, staging_dir: &Path) {
+        if staging_dir.exists() {
+            if let Err(e) = remove_dir_all(staging_dir) {
+                println!("{} {}: {}", "Unable to remove".red(), staging_dir.to_string_lossy(), e);
+            }
+        }
+    }
+
+    // Every step here is safe to re-run, `blue-eyes setup` uses it to finish skipped steps.
+    pub fn setup(&self) -> Result<(), String> {
+        let mut created_database = None;
+        let result = self.run_setup(&mut created_database);
+
+        // a database an earlier run created is kept, only the one this run created is rolled back
+        if let (Err(_), Some(connection_string)) = (&result, created_database) {
+            if let Err(e) = db::drop_database(&connection_string) {
+                println!("{} {}", "Unable to drop the database:".red(), e);
+            }
+        }
+
+        result
+    }
+
+    fn run_setup(&self, created_database: &mut Option<String>) -> Result<(), String> {
+        let mut skipped = vec![];
 
-        if !cmd.status.success() {
-            return Err("Failed to init tailwind.".to_string());
+        // install the gems listed in the Gemfile
+        if self.skip_bundle || self.offline {
+            skipped.push("bundle");
+        } else {
+            step("bundle install", self.run_bundle())?;
+        }
+
+        // bin/dev loses execute, add back
+        step("chmod bin/dev", self.chmod_x(Dir::Bin(Some("dev")).path()))?;
+
+        // run initial migrate, required for user model, needs the installed gems
+        if self.skip_migrate || self.skip_bundle || self.offline {
+            skipped.push("migrate");
+        } else {
+            // postgres and mysql databases must exist before migrating
+            let connection_string = environment::database_url()?;
+            if step("db create", db::create_database(&connection_string))? {
+                *created_database = Some(connection_string);
+            }
+            step("migrate", self.run_migrate())?;
+        }
+
+        // download tailwind for this platform, or reuse a cached copy
+        if self.skip_tailwind || !step("install tailwind", self.download_tailwind())? {
+            skipped.push("tailwind");
         }
 
+        if self.skip_git {
+            skipped.push("git");
+        } else {
+            step("git init", self.git_init())?;
+        }
+
+        if !skipped.is_empty() {
+            println!(
+                "\n{} {}",
+                "Skipped:".yellow(),
+                skipped.join(", ").yellow().bold()
+            );
+            println!("{}", "Run `blue-eyes setup` in the project directory to finish.".yellow());
+        }
+
+        Ok(())
+    }
+
+    fn download_tailwind(&self) -> Result<bool, String> {
+        let output_path = Dir::Bin(Some("tailwindcss")).path();
+        if Path::new(&output_path).is_file() {
+            println!("{}", "Tailwind already installed".blue());
+            return Ok(true);
+        }
+
+        let tailwind = Tailwind::new(&self.tailwind_version);
+        if self.offline && !tailwind.is_available_offline() {
+            return Ok(false);
+        }
+
+        println!("{}", "Installing tailwind".blue());
+        if let Err(e) = tailwind.install(&output_path) {
+            return Err(e.to_string());
+        }
+
+        if let Err(e) = self.chmod_x(output_path) {
+            return Err(e.to_string());
+        }
+
+        Ok(true)
+    }
+
+    fn create_manifest(&self) -> Result<(), String> {
+        println!("{}", "Creating project manifest".green());
+
+        let manifest = Manifest::new(&self.name, self.db, &self.tailwind_version);
+        match manifest.write(self.write_mode) {
+            Ok(_) => Ok(()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    fn create_tailwind_config(&self) -> Result<(), String> {
+        println!("{}", "Creating tailwind config".green());
+
         let output_path = Dir::Root(Some("tailwind.config.js")).path();
         let template_path = "tailwind_config.template".to_string();
 
-        match write_template(output_path, template_path, &Context::new()) {
+        match write_template(output_path, template_path, &Context::new(), self.write_mode) {
             Ok(_) => Ok(()),
             Err(e) => Err(e.to_string()),
         }
     }
 
+    fn git_init(&self) -> Result<(), String> {
+        if Path::new(&Dir::Root(Some(".git")).path()).exists() {
+            println!("{}", "Git repository already initialized".blue());
+            return Ok(());
+        }
+
+        println!("{}", "Initializing git repository".green());
+        let output = Command::new("git")
+            .arg("init")
+            .arg("--quiet")
+            .output()
+            .map_err(|e| e.to_string())?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).to_string());
+        }
+
+        Ok(())
+    }
+
     fn run_migrate(&self) -> Result<(), String> {
-        print!("{}", "Running migrations for ".green());
-        println!("{}", self.connection_string.clone().unwrap().green().bold());
+        println!("{}", "Running migrations".green());
 
         migrate::run()?;
         Ok(())
@@ -136,17 +367,17 @@ impl Project {
             .output()
             .map_err(|e| e.to_string())?;
         if !output.status.success() {
-            return Err(String::from_utf8(output.stderr).unwrap());
+            return Err(String::from_utf8_lossy(&output.stderr).to_string());
         }
 
         Ok(())
     }
 
-    fn run_bundle(&self, db: String) -> Result<(), String> {
+    fn run_bundle(&self) -> Result<(), String> {
         println!("{}", "Installing default Gems".green());
 
         let bundler = Bundler::new();
-        match bundler.install(&db) {
+        match bundler.install() {
             Ok(_) => Ok(()),
             Err(e) => Err(e.to_string()),
         }
@@ -158,25 +389,12 @@ impl Project {
         let output_path = Dir::Root(Some("config.ru")).path();
         let template_path = "config_ru.template".to_string();
 
-        match write_template(output_path, template_path, &Context::new()) {
+        match write_template(output_path, template_path, &Context::new(), self.write_mode) {
             Ok(_) => Ok(()),
             Err(e) => Err(e.to_string()),
         }
     }
 
-    fn cd_app_dir(&self) -> Result<(), String> {
-        println!("{}", "Moving into app directory".green());
-
-        let app_path = Dir::Root(Some(&self.name)).path();
-        let app_dir = Path::new(&app_path);
-
-        if let Err(e) = env::set_current_dir(app_dir) {
-            return Err(e.to_string());
-        }
-
-        Ok(())
-    }
-
     fn generate_secret(&self) -> String {
         let mut key = [0u8; 32];  // 32 bytes = 256 bits
         OsRng.fill_bytes(&mut key);  // Fill with cryptographically secure random bytes
@@ -186,26 +404,39 @@ impl Project {
     }
 
     fn create_env_file(&self) -> Result<String, String> {
-        println!("{}", "Creating .env file".green());
+        println!("{}", "Creating .env files".green());
 
         let output_path = Dir::Root(Some(".env")).path();
         let template_path = "env_file.template".to_string();
 
-        let pg_connection_string = "postgres://".to_string() + self.name.as_str();
-        let sqlite_connection_string = "sqlite://".to_string() + self.name.as_str() + ".db";
-        let connection_string = match self.db {
-            _ if self.db.to_string().trim() == "postgres" => pg_connection_string,
-            _ => sqlite_connection_string,
-        };
+        let mut context = Context::new();
+        context.insert("secret", self.generate_secret().as_str());
 
-        let secret = self.generate_secret();
+        if let Err(e) = write_template(output_path, template_path, &context, self.write_mode) {
+            return Err(e.to_string());
+        }
+
+        // each environment gets its own database so tests never touch development data
+        let connection_string = self.db.connection_string(&self.name);
+        self.create_env_database_file(Environment::Development, &connection_string)?;
+        self.create_env_database_file(
+            Environment::Test,
+            &self.db.connection_string(&(self.name.clone() + "_test")),
+        )?;
+
+        Ok(connection_string)
+    }
+
+    fn create_env_database_file(&self, environment: Environment, connection_string: &str) -> Result<(), String> {
+        let filename = ".env.".to_string() + environment.as_str();
+        let output_path = Dir::Root(Some(&filename)).path();
+        let template_path = "env_database.template".to_string();
 
         let mut context = Context::new();
-        context.insert("connection_string", connection_string.as_str());
-        context.insert("secret", secret.as_str());
+        context.insert("connection_string", connection_string);
 
-        match write_template(output_path, template_path, &context) {
-            Ok(_) => Ok(connection_string),
+        match write_template(output_path, template_path, &context, self.write_mode) {
+            Ok(_) => Ok(()),
             Err(e) => Err(e.to_string()),
         }
     }
@@ -216,28 +447,34 @@ impl Project {
         let output_path = Dir::BundleConfig(Some("config")).path();
         let template_path = "bundle_config.template".to_string();
 
-        match write_template(output_path, template_path, &Context::new()) {
+        match write_template(output_path, template_path, &Context::new(), self.write_mode) {
             Ok(_) => Ok(()),
             Err(e) => Err(e.to_string()),
         }
     }
 
-    fn get_gemfile_context(&self) -> Context {
+    fn get_gemfile_context(&self) -> Result<Context, String> {
+        // without bundling the Gemfile can be written on a machine without ruby, leaving the version out
         let ruby_version = match self.get_ruby_version() {
-            Ok(ruby_version) => ruby_version,
-            Err(error) => panic!("{}", error),
+            Ok(ruby_version) => Some(ruby_version),
+            Err(e) if self.skip_bundle || self.offline => {
+                println!("{} {}", "Leaving the ruby version out of the Gemfile:".yellow(), e.yellow());
+                None
+            }
+            Err(e) => return Err(e),
         };
 
         let mut context = Context::new();
-        context.insert("ruby_version", ruby_version.as_str());
+        context.insert("ruby_version", &ruby_version);
+        context.insert("gems", &Bundler::new().gems_for(self.db));
 
-        context
+        Ok(context)
     }
     pub fn get_ruby_version(&self) -> Result<String, String> {
         let output = Command::new("ruby")
             .arg("--version")
             .output()
-            .map_err(|err| err.to_string())?;
+            .map_err(|err| format!("ruby --version: {}", err))?;
 
         let full_version = match String::from_utf8(output.stdout) {
             Ok(v) => v,
@@ -257,30 +494,28 @@ impl Project {
 
         let output_path = Dir::Root(Some("Gemfile")).path();
         let template_path = "gemfile.template".to_string();
-        let context = self.get_gemfile_context();
-        match write_template(output_path, template_path, &context) {
+        let context = self.get_gemfile_context()?;
+        match write_template(output_path, template_path, &context, self.write_mode) {
             Ok(_) => Ok(()),
             Err(e) => Err(e.to_string()),
         }
     }
 
-    fn copy_project_template(&self, template_dir: PathBuf) -> Result<(), String> {
+    fn copy_project_template(&self) -> Result<(), String> {
         println!("{}", "Copying project template files".green());
 
         for file in Templates::iter() {
             if let Some(content) = Templates::get(&file) {
-                let dest_path = template_dir.join(String::from(file));
-                if let Some(parent) = dest_path.parent() {
-                    create_dir_all(parent).unwrap();
-                }
-                let mut output_file = File::create(&dest_path).unwrap();
-                match output_file.write_all(content.data.as_ref()) {
-                    Ok(_) => continue,
-                    Err(e) => return Err(e.to_string()),
-                }
+                let dest_path = Dir::Root(Some(&file)).path();
+                write_file(&dest_path, content.data.as_ref(), self.write_mode)?;
             }
         }
 
         Ok(())
     }
 }
+
+// Names the step in the error so a failed generate reports where it stopped.
+fn step<T>(name: &str, result: Result<T, String>) -> Result<T, String> {
+    result.map_err(|e| format!("{} failed: {}", name, e))
+}
@@ -1,20 +1,19 @@
-use crate::cli_commands::cli::{CommandType, SharedArgs};
+use crate::cli_commands::cli::{CommandType, ServiceArgs, SharedArgs};
+use crate::cli_commands::field::{Field, FieldType};
 use crate::dirs::Dir;
+use colored::Colorize;
 use inflector::Inflector;
-use serde::Serialize;
-use std::collections::HashMap;
 use std::fs;
+use std::path::Path;
 use chrono::Utc;
 use tera::Context;
-use toml::Value;
-use crate::template_writer::write_template;
+use crate::template_writer::{render, update_file, write_template, WriteMode};
+use crate::migrate;
+use crate::paths_config::{PathsConfig, ResourcePath};
+use crate::inflections::{pluralize, singularize};
 
-#[allow(dead_code)]
-#[derive(Debug, Clone, Serialize)]
-struct Field {
-    name: String,
-    sql_type: String,
-}
+const VIEWS: [&str; 5] = ["index", "new", "show", "edit", "form"];
+const SERVICE_ACTIONS: [&str; 5] = ["index", "show", "create", "update", "destroy"];
 
 #[allow(dead_code)]
 pub enum NameVariant {
@@ -28,93 +27,618 @@ pub enum NameVariant {
     BelongsToModel,
     BelongsToPath,
     BelongsToId,
+    ControllerPath,
+    Helper,
+    Service,
 }
 
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
 pub struct Resource {
     pub name: String,
+    namespace: Vec<String>,
     fields: Option<Vec<Field>>,
 
     #[allow(dead_code)]
     alias: Option<String>,
 
     belongs_to: Option<String>,
+    actions: Vec<String>,
+    with_controller: bool,
     for_command: CommandType,
+    write_mode: WriteMode,
 }
 
 impl Resource {
-    pub fn new(args: &SharedArgs, cmd_type: CommandType) -> Self {
-        let field_list = match &args.fields {
-            Some(f) => {
-                let mut new_fields = Vec::new();
-                for field in f {
-                    let parts: Vec<&str> = field.split(":").collect();
-
-                    new_fields.push(Field {
-                        name: String::from(parts[1]),
-                        sql_type: String::from(parts[0]),
-                    });
-                }
-                Some(new_fields)
-            }
-            _ => None,
+    pub fn new(args: &SharedArgs, cmd_type: CommandType) -> Result<Self, String> {
+        let field_list = match args.fields.as_deref() {
+            Some(fields) => Some(Field::parse_list(fields)?),
+            None => None,
         };
 
-        Resource {
-            name: args.name.clone(),
+        let (namespace, name) = Resource::split_namespace(&args.name)?;
+
+        Ok(Resource {
+            name,
+            namespace,
             fields: field_list,
             alias: args.alias.clone(),
             belongs_to: args.belongs_to.clone(),
+            actions: vec![],
+            with_controller: false,
+            for_command: cmd_type,
+            write_mode: args.write.mode(),
+        })
+    }
+
+    pub fn service(args: &ServiceArgs) -> Result<Self, String> {
+        let field_list = match args.fields.as_deref() {
+            Some(fields) => Some(Field::parse_list(fields)?),
+            None => None,
+        };
+
+        let (namespace, name) = Resource::split_namespace(&args.name)?;
+
+        let mut actions: Vec<String> = vec![];
+        for action in &args.actions {
+            let is_identifier = action.starts_with(|c: char| c.is_ascii_alphabetic())
+                && action.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+            if !is_identifier {
+                return Err(format!("Invalid action '{}': expected a ruby method name", action));
+            }
+
+            let action = action.to_snake_case();
+            if !actions.contains(&action) {
+                actions.push(action);
+            }
+        }
+        if actions.is_empty() {
+            actions = SERVICE_ACTIONS.iter().map(|action| action.to_string()).collect();
+        }
+
+        // the views link to every CRUD route, so the controller needs all of them
+        let missing: Vec<&str> = SERVICE_ACTIONS
+            .iter()
+            .filter(|action| !actions.iter().any(|a| a == *action))
+            .copied()
+            .collect();
+        if args.with_controller && !missing.is_empty() {
+            return Err(format!(
+                "--with-controller needs the {} actions the views link to, add {} or drop --with-controller",
+                SERVICE_ACTIONS.join(", "),
+                missing.join(" ")
+            ));
+        }
+
+        Ok(Resource {
+            name,
+            namespace,
+            fields: field_list,
+            alias: args.alias.clone(),
+            belongs_to: None,
+            actions,
+            with_controller: args.with_controller,
+            for_command: CommandType::Service,
+            write_mode: args.write.mode(),
+        })
+    }
+
+    pub fn named(name: &str, cmd_type: CommandType) -> Result<Self, String> {
+        let (namespace, name) = Resource::split_namespace(name)?;
+
+        Ok(Resource {
+            name,
+            namespace,
+            fields: None,
+            alias: None,
+            belongs_to: None,
+            actions: vec![],
+            with_controller: false,
             for_command: cmd_type,
+            write_mode: WriteMode::default(),
+        })
+    }
+
+    // admin/post is the post resource inside the admin namespace
+    fn split_namespace(name: &str) -> Result<(Vec<String>, String), String> {
+        let mut segments: Vec<String> = name.split('/').map(|segment| segment.to_snake_case()).collect();
+        if segments.iter().any(|segment| segment.is_empty()) {
+            return Err(format!("Invalid name '{}': expected name or namespace/name", name));
+        }
+
+        let name = segments.pop().unwrap_or_default();
+        Ok((segments, name))
+    }
+
+    // The namespace joined with the separator, ending with it, e.g. "Admin::" or "admin/"
+    fn namespace_prefix(&self, separator: &str, pascal: bool) -> String {
+        self.namespace
+            .iter()
+            .map(|segment| match pascal {
+                true => segment.to_pascal_case() + separator,
+                false => segment.clone() + separator,
+            })
+            .collect()
+    }
+
+    // Every module a namespaced controller is nested in, outermost first
+    fn namespace_modules(&self) -> Vec<String> {
+        let mut modules: Vec<String> = vec![];
+        for segment in &self.namespace {
+            let module = match modules.last() {
+                Some(parent) => parent.clone() + "::" + &segment.to_pascal_case(),
+                None => segment.to_pascal_case(),
+            };
+            modules.push(module);
+        }
+
+        modules
+    }
+
+    // Within a namespace a parent resolves to the namespaced resource first, e.g. admin_authors before authors
+    fn parent_resource(&self, paths_config: &PathsConfig, belongs_to: &str) -> String {
+        let parent = self.variant(NameVariant::BelongsToPath, belongs_to.to_string());
+        let namespaced = self.namespace_prefix("_", false) + &parent;
+
+        match paths_config.get(&namespaced) {
+            Some(_) => namespaced,
+            None => parent,
         }
     }
 
     pub fn generate_template(&self) -> Result<(), String> {
         println!("Generating template... {:?}", &self.for_command);
+
+        // a conflict on any file stops the generator before the first one is written
+        if self.write_mode == WriteMode::Check {
+            Resource { write_mode: WriteMode::Preflight, ..self.clone() }.generate_files()?;
+        }
+
+        self.generate_files()
+    }
+
+    fn generate_files(&self) -> Result<(), String> {
         match &self.for_command {
-            // CommandType::Api => println!("Api"),
+            CommandType::Api => {
+                self.generate_path_config()?;
+                self.generate_controller()?;
+                Ok(())
+            }
             CommandType::Controller => {
+                self.generate_path_config()?;
                 self.generate_controller()?;
-                _ = self.generate_path_config();
+                self.generate_views()?;
                 Ok(())
             }
             CommandType::Model => {
+                self.check_not_namespaced()?;
+                self.check_applied_migration()?;
                 self.generate_model()?;
                 self.generate_migration()?;
                 Ok(())
             }
             CommandType::Scaffold => {
+                if self.namespace.is_empty() {
+                    self.check_applied_migration()?;
+                }
+                self.generate_path_config()?;
                 self.generate_controller()?;
-                _ = self.generate_path_config();
-                self.generate_model()?;
-                self.generate_migration()?;
+                self.generate_views()?;
+                // admin/post sits on top of the top-level Post model
+                if self.namespace.is_empty() {
+                    self.generate_model()?;
+                    self.generate_migration()?;
+                } else {
+                    self.report_shared_model();
+                }
                 Ok(())
             },
+            CommandType::Service => {
+                self.check_service_model()?;
+                if self.with_controller {
+                    self.generate_path_config()?;
+                }
+                self.generate_service()?;
+                if self.with_controller {
+                    self.generate_controller()?;
+                    self.generate_views()?;
+                }
+                Ok(())
+            }
+            _ => Err("Not implemented".to_string()),
+        }
+    }
+
+    // Models and tables are shared by every namespace, admin/post and post both use Post
+    fn check_not_namespaced(&self) -> Result<(), String> {
+        match self.namespace.is_empty() {
+            true => Ok(()),
+            false => Err(format!("Models are not namespaced, use `{}` instead", self.name)),
+        }
+    }
+
+    // --force would rewrite an applied create migration, and the table would never change
+    fn check_applied_migration(&self) -> Result<(), String> {
+        if self.write_mode != WriteMode::Force {
+            return Ok(());
+        }
+
+        let existing = match self.create_migrations()?.pop() {
+            Some(existing) => existing,
+            None => return Ok(()),
+        };
+        let content = render("new_table.template", &self.get_migration_context()?)?;
+        let unchanged = fs::read_to_string(Dir::Migrations(Some(&existing)).path())
+            .is_ok_and(|current| current == content);
+        if unchanged || !Resource::applied_migrations()?.contains(&existing) {
+            return Ok(());
+        }
+
+        Err(format!(
+            "Migration {} has already been applied, change the table with `blue-eyes g migration alter {}` instead",
+            existing, self.name
+        ))
+    }
+
+    // The standard actions and the controller call the model, which the service generator doesn't write
+    fn check_service_model(&self) -> Result<(), String> {
+        let uses_model = self.with_controller
+            || self.actions.iter().any(|action| SERVICE_ACTIONS.contains(&action.as_str()));
+        if !uses_model || self.model_file(&self.name).is_some() {
+            return Ok(());
+        }
+
+        Err(format!(
+            "{} needs the {} model, generate it first with `blue-eyes g model {}`",
+            self.variant(NameVariant::Service, self.name.clone()),
+            self.variant(NameVariant::Model, self.name.clone()),
+            self.name
+        ))
+    }
+
+    // Generated models are plural, posts.rb, the ones shipped with the project singular, user.rb
+    fn model_file(&self, name: &str) -> Option<String> {
+        let name = name.to_snake_case();
+        [pluralize(&name), singularize(&name)]
+            .iter()
+            .map(|filename| Dir::Models(Some(&(filename.clone() + ".rb"))).path())
+            .find(|path| Path::new(path).is_file())
+    }
+
+    fn report_shared_model(&self) {
+        if self.write_mode == WriteMode::Preflight {
+            return;
+        }
+
+        let filename = self.variant(NameVariant::Path, self.name.clone()) + ".rb";
+        let model_path = Dir::Models(Some(&filename)).path();
+        match Path::new(&model_path).is_file() {
+            true => println!("{} {}", "Using the top-level model".blue(), model_path.blue().bold()),
+            false => println!(
+                "{} {}",
+                "No model found, generate it with".yellow(),
+                format!("blue-eyes g model {}", self.name).yellow().bold()
+            ),
+        }
+    }
+
+    pub fn destroy_template(&self) -> Result<(), String> {
+        println!("Destroying template... {:?}", &self.for_command);
+        match &self.for_command {
+            CommandType::Api => {
+                self.destroy_path_config()?;
+                self.destroy_controller()?;
+                Ok(())
+            }
+            CommandType::Controller => {
+                self.destroy_path_config()?;
+                self.destroy_controller()?;
+                self.destroy_views()?;
+                Ok(())
+            }
+            CommandType::Model => {
+                self.check_not_namespaced()?;
+                let migrations = self.removable_migrations()?;
+                self.destroy_model()?;
+                self.destroy_files(migrations)
+            }
+            CommandType::Scaffold => {
+                // the top-level model belongs to the un-namespaced resource, leave it alone
+                if !self.namespace.is_empty() {
+                    self.destroy_path_config()?;
+                    self.destroy_controller()?;
+                    return self.destroy_views();
+                }
+
+                let migrations = self.removable_migrations()?;
+                self.destroy_path_config()?;
+                self.destroy_controller()?;
+                self.destroy_views()?;
+                self.destroy_model()?;
+                self.destroy_files(migrations)
+            }
+            CommandType::Service => {
+                // undo --with-controller when it was used
+                let controller = self.variant(NameVariant::ControllerPath, self.name.clone()) + ".rb";
+                if Path::new(&Dir::Controllers(Some(&controller)).path()).is_file() {
+                    self.destroy_path_config()?;
+                    self.destroy_controller()?;
+                    self.destroy_views()?;
+                }
+
+                // like models, services are shared with the namespaced controllers
+                if !self.namespace.is_empty() {
+                    return Ok(());
+                }
+
+                let filename = self.variant(NameVariant::Path, self.name.clone()) + "_service.rb";
+                self.destroy_files(vec![Dir::Services(Some(&filename)).path()])
+            }
             _ => Err("Not implemented".to_string()),
         }
     }
 
+    fn destroy_files(&self, paths: Vec<String>) -> Result<(), String> {
+        for path in paths {
+            match fs::remove_file(&path) {
+                Ok(_) => println!("{} {}", "remove".red(), path),
+                Err(_) => println!("{} {}", "skip".yellow(), path),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn destroy_controller(&self) -> Result<(), String> {
+        let filename = self.variant(NameVariant::ControllerPath, self.name.clone()) + ".rb";
+        self.destroy_files(vec![Dir::Controllers(Some(&filename)).path()])?;
+        self.destroy_namespace_dirs(Dir::Controllers(None).path());
+
+        let contents = match fs::read_to_string(Dir::Root(Some("config.ru")).path()) {
+            Ok(contents) => contents,
+            Err(e) => return Err(e.to_string()),
+        };
+
+        let use_line = "use ".to_string() + &self.variant(NameVariant::Class, self.name.clone()) + "Controller";
+        let result: Vec<&str> = contents.split('\n').filter(|line| line.trim() != use_line).collect();
+        match fs::write(Dir::Root(Some("config.ru")).path(), result.join("\n")) {
+            Ok(_) => Ok(()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    fn destroy_views(&self) -> Result<(), String> {
+        let haml = self.variant(NameVariant::Haml, self.name.clone());
+        let paths = VIEWS
+            .iter()
+            .map(|view| Dir::Views(Some(&(haml.clone() + "_" + view + ".haml"))).path())
+            .collect();
+
+        self.destroy_files(paths)?;
+        self.destroy_namespace_dirs(Dir::Views(None).path());
+        Ok(())
+    }
+
+    // Innermost first, remove_dir keeps a namespace directory that still holds other resources
+    fn destroy_namespace_dirs(&self, base: String) {
+        for depth in (1..=self.namespace.len()).rev() {
+            let dir = Path::new(&base).join(self.namespace[..depth].join("/"));
+            if fs::remove_dir(&dir).is_ok() {
+                println!("{} {}", "remove".red(), dir.to_string_lossy());
+            }
+        }
+    }
+
+    fn destroy_model(&self) -> Result<(), String> {
+        let filename = self.variant(NameVariant::Path, self.name.clone()) + ".rb";
+        self.destroy_files(vec![Dir::Models(Some(&filename)).path()])?;
+
+        // drop the one_to_many that generate_model added to each parent
+        let association = "  one_to_many :".to_string() + &self.variant(NameVariant::Path, self.name.clone());
+        let entries = match fs::read_dir(Dir::Models(None).path()) {
+            Ok(entries) => entries,
+            Err(e) => return Err(e.to_string()),
+        };
+
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            let path = entry.path();
+            let contents = match fs::read_to_string(&path) {
+                Ok(contents) => contents,
+                Err(_) => continue,
+            };
+            if !contents.lines().any(|line| line == association) {
+                continue;
+            }
+
+            let result: Vec<&str> = contents.split('\n').filter(|line| *line != association).collect();
+            if let Err(e) = fs::write(&path, result.join("\n")) {
+                return Err(e.to_string());
+            }
+        }
+
+        Ok(())
+    }
+
+    // <version>_create_<table>.rb files for this resource, oldest first
+    fn create_migrations(&self) -> Result<Vec<String>, String> {
+        let suffix = "_create_".to_string() + &self.variant(NameVariant::Path, self.name.clone()) + ".rb";
+        let entries = match fs::read_dir(Dir::Migrations(None).path()) {
+            Ok(entries) => entries,
+            Err(e) => return Err(e.to_string()),
+        };
+
+        let mut filenames: Vec<String> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .filter(|filename| match filename.strip_suffix(&suffix) {
+                Some(version) => !version.is_empty() && version.chars().all(|c| c.is_ascii_digit()),
+                None => false,
+            })
+            .collect();
+        filenames.sort();
+
+        Ok(filenames)
+    }
+
+    fn removable_migrations(&self) -> Result<Vec<String>, String> {
+        let filenames = self.create_migrations()?;
+        if filenames.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let applied = Resource::applied_migrations()?;
+        if let Some(filename) = filenames.iter().find(|filename| applied.contains(filename)) {
+            return Err(
+                "Migration ".to_string() + filename
+                    + " has already been applied, roll it back with `blue-eyes migrate rollback` first",
+            );
+        }
+
+        Ok(filenames
+            .iter()
+            .map(|filename| Dir::Migrations(Some(filename)).path())
+            .collect())
+    }
+
+    // Without ruby the generator can't tell whether a create migration is safe to touch
+    fn applied_migrations() -> Result<Vec<String>, String> {
+        migrate::applied_migrations().map_err(|e| {
+            format!("Unable to check schema_migrations, which needs `bundle exec sequel` in the project: {}", e)
+        })
+    }
+
+    fn destroy_path_config(&self) -> Result<(), String> {
+        let mut paths_config = PathsConfig::load()?;
+        let name = self.variant(NameVariant::Helper, self.name.clone());
+
+        if let Some(child) = paths_config.resources.iter().find(|r| r.belongs_to.as_ref() == Some(&name)) {
+            return Err(format!("{} belongs to {}, destroy it first", child.name, name));
+        }
+
+        if paths_config.remove(&name) {
+            paths_config.save(self.write_mode)?;
+        }
+
+        Ok(())
+    }
+
     fn generate_model(&self) -> Result<(), String> {
         let filename = self.variant(NameVariant::Path, self.name.clone()) + ".rb";
-        let context = self.get_context()?;
+        let mut context = self.get_context()?;
+        context.insert("many_to_one", &self.parents());
         let output_path = Dir::Models(Some(&filename)).path();
 
-        match write_template(output_path, "model.template".to_string(), &context) {
+        if let Err(e) = write_template(output_path, "model.template".to_string(), &context, self.write_mode) {
+            return Err(e.to_string());
+        }
+
+        for parent in self.parents() {
+            self.update_parent_model(parent)?;
+        }
+
+        Ok(())
+    }
+
+    fn parents(&self) -> Vec<String> {
+        let mut parents: Vec<String> = self
+            .fields
+            .iter()
+            .flatten()
+            .filter(|field| field.sql_type == FieldType::References)
+            .map(|field| field.name.clone())
+            .collect();
+
+        if let Some(belongs_to) = &self.belongs_to {
+            let parent = self.variant(NameVariant::BelongsToId, belongs_to.clone());
+            if !parents.contains(&parent) {
+                parents.push(parent);
+            }
+        }
+
+        parents
+    }
+
+    fn update_parent_model(&self, parent: String) -> Result<(), String> {
+        let output_path = match self.model_file(&parent) {
+            Some(output_path) => output_path,
+            None => {
+                if self.write_mode != WriteMode::Preflight {
+                    let filename = self.variant(NameVariant::Path, parent.clone()) + ".rb";
+                    println!(
+                        "{} {}",
+                        "Skipping one_to_many, parent model not found:".yellow(),
+                        Dir::Models(Some(&filename)).path().yellow().bold()
+                    );
+                }
+                return Ok(());
+            }
+        };
+
+        let contents = match fs::read_to_string(&output_path) {
+            Ok(contents) => contents,
+            Err(e) => return Err(output_path + ": " + &e.to_string()),
+        };
+
+        let association = "  one_to_many :".to_string() + &self.variant(NameVariant::Path, self.name.clone());
+        if contents.lines().any(|line| line == association) {
+            return Ok(());
+        }
+
+        let class_line = "class ".to_string() + &self.variant(NameVariant::Model, parent) + " < Sequel::Model";
+        if !contents.contains(&class_line) {
+            return Err("Unable to find '".to_string() + &class_line + "' in " + &output_path);
+        }
+
+        let result = contents.replacen(&class_line, &(class_line.clone() + "\n" + &association), 1);
+        match update_file(&output_path, &result, self.write_mode) {
             Ok(_) => Ok(()),
             Err(e) => Err(e.to_string()),
         }
     }
 
+    fn generate_service(&self) -> Result<(), String> {
+        let filename = self.variant(NameVariant::Path, self.name.clone()) + "_service.rb";
+        let output_path = Dir::Services(Some(&filename)).path();
+
+        let context = self.get_context()?;
+
+        match write_template(output_path, "service.template".to_string(), &context, self.write_mode) {
+            Ok(_) => Ok(()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    fn generate_views(&self) -> Result<(), String> {
+        let context = self.get_context()?;
+        let haml = self.variant(NameVariant::Haml, self.name.clone());
+
+        for view in VIEWS {
+            let filename = haml.clone() + "_" + view + ".haml";
+            let output_path = Dir::Views(Some(&filename)).path();
+            let template_path = "view_".to_string() + view + ".template";
+
+            if let Err(e) = write_template(output_path, template_path, &context, self.write_mode) {
+                return Err(e.to_string());
+            }
+        }
+
+        Ok(())
+    }
+
     fn generate_migration(&self) -> Result<(), String> {
-        let timestamp = Utc::now().timestamp();
-        let filename = timestamp.to_string() + "_create_" + &self.variant(NameVariant::Path, self.name.clone()) + ".rb";
+        // an existing create migration is compared like any other file instead of getting a second one
+        let filename = match self.create_migrations()?.pop() {
+            Some(existing) => existing,
+            None => {
+                let timestamp = Utc::now().timestamp();
+                timestamp.to_string() + "_create_" + &self.variant(NameVariant::Path, self.name.clone()) + ".rb"
+            }
+        };
         let output_path = Dir::Migrations(Some(filename.as_str())).path();
 
         let context = self.get_migration_context()?;
 
-
-        match write_template(output_path, "new_table.template".to_string(), &context) {
+        match write_template(output_path, "new_table.template".to_string(), &context, self.write_mode) {
             Ok(_) => Ok(()),
             Err(e) => Err(e.to_string()),
         }
@@ -126,9 +650,23 @@ impl Resource {
 
         context.insert("table_name", &table_name);
 
+        // --belongs-to author together with author:references declares the foreign key once
+        let references_column = |column: &str| {
+            self.fields
+                .iter()
+                .flatten()
+                .any(|field| field.sql_type == FieldType::References && field.column == column)
+        };
+
         if let Some(belongs_to) = &self.belongs_to {
             let belongs_to_id = self.variant(NameVariant::BelongsToId, belongs_to.clone()) + "_id";
-            context.insert("belongs_to_id", &belongs_to_id);
+            if !references_column(&belongs_to_id) {
+                context.insert("belongs_to_id", &belongs_to_id);
+            }
+            context.insert(
+                "belongs_to_table",
+                &self.variant(NameVariant::BelongsToPath, belongs_to.clone()),
+            );
         }
         if let Some(fields) = &self.fields {
             context.insert("fields", fields);
@@ -139,13 +677,16 @@ impl Resource {
         Ok(context)
     }
     fn get_context(&self) -> Result<Context, String> {
-        let alias_lookup = match self.load_paths_config() {
-            Ok(alias_lookup) => alias_lookup,
-            Err(e) => Err(e.to_string())?,
-        };
+        let paths_config = PathsConfig::load()?;
 
         let mut context = Context::new();
         context.insert("haml", &self.variant(NameVariant::Haml, self.name.clone()));
+        context.insert("fields", &self.fields.clone().unwrap_or_default());
+        context.insert("has_belongs_to", &self.belongs_to.is_some());
+        context.insert("namespace_path", &self.namespace_prefix("/", false));
+        context.insert("namespace_modules", &self.namespace_modules());
+        context.insert("service", &self.variant(NameVariant::Service, self.name.clone()));
+        context.insert("actions", &self.actions);
         context.insert(
             "variable",
             &self.variant(NameVariant::Variable, self.name.clone()),
@@ -183,15 +724,14 @@ impl Resource {
                 "belongs_to_id",
                 &self.variant(NameVariant::BelongsToId, belongs_to.clone()),
             );
+            // a parent without a controller has no alias, its path is the plural name
+            let parent_path = match paths_config.get(&self.parent_resource(&paths_config, belongs_to)) {
+                Some(resource) => resource.path().to_string(),
+                None => self.variant(NameVariant::BelongsToPath, belongs_to.to_string()),
+            };
             context.insert(
                 "belongs_to_path",
-                &self.variant(
-                    NameVariant::BelongsToPath,
-                    alias_lookup
-                        .get(&self.variant(NameVariant::BelongsToPath, belongs_to.to_string()))
-                        .unwrap()
-                        .clone(),
-                ),
+                &self.variant(NameVariant::BelongsToPath, parent_path),
             );
         } else {
             context.insert(
@@ -211,94 +751,51 @@ impl Resource {
         Ok(context)
     }
 
-    fn get_path_config_context(
-        &self,
-        name: String,
-        alias: Option<String>,
-        belongs_to: Option<String>,
-    ) -> Context {
-        let mut context = Context::new();
-        context.insert("name", &self.variant(NameVariant::Path, name));
-
-        if let Some(alias) = alias {
-            context.insert("alias", &self.variant(NameVariant::Path, alias));
-            context.insert("has_alias", &true);
-        } else {
-            context.insert("alias", "");
-            context.insert("has_alias", &false);
-        }
-        if let Some(belongs_to) = belongs_to {
-            context.insert("belongs_to", &self.variant(NameVariant::Path, belongs_to));
-            context.insert("has_belongs_to", &true);
-        } else {
-            context.insert("belongs_to", "");
-            context.insert("has_belongs_to", &false);
-        }
-
-        context
-    }
-
-    fn load_paths_config(&self) -> Result<HashMap<String, String>, String> {
-        let content = fs::read_to_string(Dir::Helpers(Some("paths_config.toml")).path())
-            .unwrap_or_else(|e| e.to_string());
-
-        let parsed: Value = match content.parse::<Value>() {
-            Ok(v) => v,
-            Err(e) => return Err(e.to_string()),
-        };
-
-        let mut hash: HashMap<String, String> = HashMap::new();
-
-        if let Some(resources) = parsed.get("resources").and_then(|v| v.as_array()) {
-            for i in resources {
-                let name = match i.get("name").and_then(|v| v.as_str()) {
-                    Some(n) => n,
-                    None => Err("Resource does not have a name")?,
-                };
-                if let Some(alias) = i.get("as").and_then(|v| v.as_str()) {
-                    if alias.is_empty() {
-                        hash.insert(name.to_string(), name.to_string());
-                    } else {
-                        hash.insert(name.to_string(), alias.to_string());
-                    }
-                } else {
-                    hash.insert(name.to_string(), name.to_string());
-                }
-            }
-        }
-
-        Ok(hash)
-    }
-
     fn generate_path_config(&self) -> Result<(), String> {
-        let context = self.get_path_config_context(
-            self.name.clone(),
-            self.alias.clone(),
-            self.belongs_to.clone(),
-        );
+        let mut paths_config = PathsConfig::load()?;
 
-        let output_path = Dir::Helpers(Some("paths_config.toml")).path();
-        let template_path = "paths_config.template".to_string();
-
-        match write_template(output_path, template_path, &context) {
+        // namespaced helpers are prefixed, get_admin_posts_path, while the url keeps the plain name
+        let alias = match (&self.alias, self.namespace.is_empty()) {
+            (Some(alias), _) => Some(self.variant(NameVariant::Path, alias.clone())),
+            (None, false) => Some(self.variant(NameVariant::Path, self.name.clone())),
+            (None, true) => None,
+        };
+        let namespace = match self.namespace.is_empty() {
+            true => None,
+            false => Some(self.namespace.join("/")),
+        };
+        let belongs_to = self
+            .belongs_to
+            .as_ref()
+            .map(|belongs_to| self.parent_resource(&paths_config, belongs_to));
+
+        paths_config.upsert(ResourcePath {
+            name: self.variant(NameVariant::Helper, self.name.clone()),
+            alias,
+            belongs_to,
+            namespace,
+        });
+
+        match paths_config.save(self.write_mode) {
             Ok(_) => Ok(()),
             Err(e) => Err(e.to_string()),
         }
     }
 
     fn generate_controller(&self) -> Result<(), String> {
-        let filename = self.variant(NameVariant::Path, self.name.clone()) + ".rb";
+        let filename = self.variant(NameVariant::ControllerPath, self.name.clone()) + ".rb";
         let has_belongs_to = self.belongs_to.is_some();
         let output_path = Dir::Controllers(Some(&filename)).path();
 
         let context = &self.get_context()?;
-        let template_path = if has_belongs_to {
-            "controller_belongs_to.template"
-        } else {
-            "controller.template"
+        let template_path = match (&self.for_command, has_belongs_to) {
+            (CommandType::Api, _) => "api_controller.template",
+            (CommandType::Service, _) => "controller_service.template",
+            (_, true) => "controller_belongs_to.template",
+            _ => "controller.template",
         };
 
-        _ = match write_template(output_path, template_path.to_string(), context) {
+        _ = match write_template(output_path, template_path.to_string(), context, self.write_mode) {
             Ok(c) => c,
             Err(e) => return Err(e.to_string()),
         };
@@ -315,27 +812,120 @@ impl Resource {
             Err(e) => return Err(e.to_string()),
         };
 
-        let replacement = "use ".to_string() + &controller_name + "\nrun Sinatra::Application";
+        let use_line = "use ".to_string() + &controller_name;
+        let result = if contents.lines().any(|line| line.trim() == use_line) {
+            contents
+        } else {
+            let replacement = use_line + "\nrun Sinatra::Application";
+            contents.replace("run Sinatra::Application", &replacement)
+        };
 
-        let result = contents.replace("run Sinatra::Application", &replacement);
-        match fs::write(Dir::Root(Some("config.ru")).path(), result) {
+        match update_file(&Dir::Root(Some("config.ru")).path(), &result, self.write_mode) {
             Ok(_) => Ok(()),
             Err(e) => Err(e.to_string()),
         }
     }
 
     pub fn variant(&self, variant: NameVariant, name: String) -> String {
+        let name = name.to_snake_case();
         match variant {
-            NameVariant::Model => name.to_pascal_case().to_singular(),
-            NameVariant::Class => name.to_pascal_case().to_plural(),
-            NameVariant::Variable => name.to_snake_case().to_singular(),
-            NameVariant::Haml => name.to_snake_case().to_plural(),
-            NameVariant::Path => name.to_snake_case().to_plural(),
-            NameVariant::Alias => name.to_snake_case().to_plural(),
-            NameVariant::BelongsToModel => name.to_pascal_case().to_singular(),
-            NameVariant::BelongsToPath => name.to_snake_case().to_plural(),
-            NameVariant::BelongsToId => name.to_snake_case().to_singular(),
-            NameVariant::VariablePlural => name.to_snake_case().to_plural(),
+            NameVariant::Model => singularize(&name).to_pascal_case(),
+            NameVariant::Class => self.namespace_prefix("::", true) + &pluralize(&name).to_pascal_case(),
+            NameVariant::Variable => singularize(&name),
+            NameVariant::Haml => self.namespace_prefix("/", false) + &pluralize(&name),
+            NameVariant::Path => pluralize(&name),
+            NameVariant::Alias => pluralize(&name),
+            NameVariant::BelongsToModel => singularize(&name).to_pascal_case(),
+            NameVariant::BelongsToPath => pluralize(&name),
+            NameVariant::BelongsToId => singularize(&name),
+            NameVariant::VariablePlural => pluralize(&name),
+            NameVariant::ControllerPath => self.namespace_prefix("/", false) + &pluralize(&name),
+            NameVariant::Helper => self.namespace_prefix("_", false) + &pluralize(&name),
+            NameVariant::Service => pluralize(&name).to_pascal_case() + "Service",
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resource(name: &str) -> Resource {
+        Resource::named(name, CommandType::Scaffold).unwrap()
+    }
+
+    #[test]
+    fn splits_the_namespace_from_the_name() {
+        assert_eq!(
+            Resource::split_namespace("admin/post").unwrap(),
+            (vec!["admin".to_string()], "post".to_string())
+        );
+        assert_eq!(
+            Resource::split_namespace("Admin/BlogPost").unwrap(),
+            (vec!["admin".to_string()], "blog_post".to_string())
+        );
+        assert_eq!(Resource::split_namespace("post").unwrap(), (vec![], "post".to_string()));
+    }
+
+    #[test]
+    fn rejects_empty_namespace_segments() {
+        assert!(Resource::split_namespace("admin//post").is_err());
+        assert!(Resource::split_namespace("/post").is_err());
+        assert!(Resource::split_namespace("admin/").is_err());
+    }
+
+    #[test]
+    fn names_a_top_level_resource() {
+        let post = resource("post");
+        let name = post.name.clone();
+
+        assert_eq!(post.variant(NameVariant::Model, name.clone()), "Post");
+        assert_eq!(post.variant(NameVariant::Class, name.clone()), "Posts");
+        assert_eq!(post.variant(NameVariant::Path, name.clone()), "posts");
+        assert_eq!(post.variant(NameVariant::Haml, name.clone()), "posts");
+        assert_eq!(post.variant(NameVariant::ControllerPath, name.clone()), "posts");
+        assert_eq!(post.variant(NameVariant::Helper, name.clone()), "posts");
+        assert_eq!(post.variant(NameVariant::Service, name), "PostsService");
+    }
+
+    #[test]
+    fn names_a_namespaced_resource() {
+        let post = resource("admin/post");
+        let name = post.name.clone();
+
+        // the model and table are shared with the top-level resource
+        assert_eq!(post.variant(NameVariant::Model, name.clone()), "Post");
+        assert_eq!(post.variant(NameVariant::Path, name.clone()), "posts");
+        assert_eq!(post.variant(NameVariant::Class, name.clone()), "Admin::Posts");
+        assert_eq!(post.variant(NameVariant::Haml, name.clone()), "admin/posts");
+        assert_eq!(post.variant(NameVariant::ControllerPath, name.clone()), "admin/posts");
+        assert_eq!(post.variant(NameVariant::Helper, name), "admin_posts");
+    }
+
+    #[test]
+    fn nests_modules_for_each_namespace() {
+        let post = resource("admin/reports/post");
+
+        assert_eq!(post.namespace_modules(), vec!["Admin".to_string(), "Admin::Reports".to_string()]);
+        assert_eq!(post.namespace_prefix("::", true), "Admin::Reports::");
+        assert_eq!(post.namespace_prefix("/", false), "admin/reports/");
+        assert_eq!(post.variant(NameVariant::Class, post.name.clone()), "Admin::Reports::Posts");
+    }
+
+    #[test]
+    fn destroys_only_empty_namespace_dirs() {
+        let base = std::env::temp_dir().join(format!("blue_eyes_namespace_{}", std::process::id()));
+        fs::create_dir_all(base.join("admin").join("reports")).unwrap();
+        fs::create_dir_all(base.join("staff").join("reports")).unwrap();
+        fs::write(base.join("staff").join("users.rb"), "").unwrap();
+
+        resource("admin/reports/post").destroy_namespace_dirs(base.to_string_lossy().to_string());
+        resource("staff/reports/post").destroy_namespace_dirs(base.to_string_lossy().to_string());
+
+        assert!(!base.join("admin").exists());
+        assert!(!base.join("staff").join("reports").exists());
+        assert!(base.join("staff").join("users.rb").exists());
+
+        fs::remove_dir_all(&base).ok();
+    }
+}
@@ -0,0 +1,381 @@
+use crate::dirs::Dir;
+use crate::inflections::singularize;
+use crate::paths_config::PathsConfig;
+use colored::Colorize;
+use inflector::Inflector;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const VERBS: [&str; 5] = ["get", "post", "put", "patch", "delete"];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Route {
+    pub verb: String,
+    pub path: String,
+    pub controller: String,
+    pub helper: Option<String>,
+}
+
+pub fn list(json: bool) -> Result<(), String> {
+    let config_routes = config_routes(&PathsConfig::load()?);
+    let routes = merge(controller_routes(&config_routes)?, config_routes);
+
+    if json {
+        println!("{}", to_json(&routes)?);
+        return Ok(());
+    }
+
+    let path_width = routes.iter().map(|r| r.path.len()).max().unwrap_or(0).max(4) + 2;
+    let controller_width = routes.iter().map(|r| r.controller.len()).max().unwrap_or(0).max(10) + 2;
+
+    println!(
+        "{:<8}{:<path_width$}{:<controller_width$}Helper",
+        "Verb", "Path", "Controller"
+    );
+    for route in routes {
+        println!(
+            "{:<8}{:<path_width$}{:<controller_width$}{}",
+            route.verb.green(),
+            route.path,
+            route.controller,
+            route.helper.unwrap_or_default().cyan()
+        );
+    }
+
+    Ok(())
+}
+
+// Adds the resources in paths_config.toml whose controller declares no routes, e.g. a deleted controller
+fn merge(mut routes: Vec<Route>, config_routes: Vec<Route>) -> Vec<Route> {
+    let declared: Vec<String> = routes.iter().map(|r| r.controller.clone()).collect();
+    routes.extend(config_routes.into_iter().filter(|r| !declared.contains(&r.controller)));
+
+    routes
+}
+
+fn to_json(routes: &[Route]) -> Result<String, String> {
+    serde_json::to_string_pretty(routes).map_err(|e| e.to_string())
+}
+
+// The routes RouteBuilder#resources defines helpers for, in the order the controller template declares them.
+fn config_routes(paths_config: &PathsConfig) -> Vec<Route> {
+    let mut routes = vec![];
+    for resource in &paths_config.resources {
+        let name = &resource.name;
+        let singular = singularize(name);
+
+        // admin_posts in the admin namespace is Admin::PostsController routed under /admin
+        let (prefix, controller) = match &resource.namespace {
+            Some(namespace) => {
+                let modules: Vec<String> = namespace.split('/').map(|segment| segment.to_pascal_case()).collect();
+                let plain = name.strip_prefix(&(namespace.replace('/', "_") + "_")).unwrap_or(name);
+                (
+                    "/".to_string() + namespace,
+                    modules.join("::") + "::" + &plain.to_pascal_case() + "Controller",
+                )
+            }
+            None => (String::new(), name.to_pascal_case() + "Controller"),
+        };
+        let member = format!("{}/{}", prefix, resource.path());
+        let collection = match &resource.belongs_to {
+            Some(belongs_to) => {
+                let parent_alias = match paths_config.get(belongs_to) {
+                    Some(parent) => parent.path(),
+                    None => belongs_to,
+                };
+                format!("{}/{}/:{}_id/{}", prefix, parent_alias, singularize(parent_alias), resource.path())
+            }
+            None => member.clone(),
+        };
+
+        let actions = [
+            ("get", collection.clone(), format!("get_{}_path", name)),
+            ("get", collection.clone() + "/new", format!("new_{}_path", singular)),
+            ("get", member.clone() + "/:id", format!("get_{}_path", singular)),
+            ("get", member.clone() + "/:id/edit", format!("edit_{}_path", singular)),
+            ("post", collection, format!("create_{}_path", singular)),
+            ("put", member.clone() + "/:id", format!("update_{}_path", singular)),
+        ];
+
+        for (verb, path, helper) in actions {
+            routes.push(Route {
+                verb: verb.to_uppercase(),
+                path,
+                controller: controller.clone(),
+                helper: Some(helper),
+            });
+        }
+        routes.push(Route {
+            verb: "DELETE".to_string(),
+            path: member + "/:id",
+            controller,
+            helper: None,
+        });
+    }
+
+    routes
+}
+
+fn controller_routes(config_routes: &[Route]) -> Result<Vec<Route>, String> {
+    let mut files = controller_files(&PathBuf::from(Dir::Controllers(None).path()))?;
+    files.sort();
+
+    let mut routes = vec![];
+    for file in files {
+        let content = fs::read_to_string(&file).map_err(|e| e.to_string())?;
+        let controller = file.file_stem().unwrap_or_default().to_string_lossy().to_pascal_case();
+        routes.extend(file_routes(&content, &controller, config_routes));
+    }
+
+    Ok(routes)
+}
+
+// The routes one controller file declares, named after its class, or the file when there is none.
+fn file_routes(content: &str, filename_controller: &str, config_routes: &[Route]) -> Vec<Route> {
+    let mut controller = filename_controller.to_string();
+
+    let mut routes = vec![];
+    for line in content.lines() {
+        let line = line.trim();
+        if let Some(class) = line.strip_prefix("class ") {
+            controller = class.split_whitespace().next().unwrap_or_default().to_string();
+            continue;
+        }
+
+        if let Some((verb, path)) = parse_declaration(line, config_routes) {
+            let helper = config_routes
+                .iter()
+                .find(|r| r.verb == verb && same_path(&r.path, &path))
+                .and_then(|r| r.helper.clone());
+
+            routes.push(Route { verb, path, controller: controller.clone(), helper });
+        }
+    }
+
+    routes
+}
+
+// Namespaced controllers live in subdirectories, app/controllers/admin/posts.rb
+fn controller_files(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => return Err(dir.to_string_lossy().to_string() + ": " + &e.to_string()),
+    };
+
+    let mut files = vec![];
+    for path in entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()) {
+        if path.is_dir() {
+            files.extend(controller_files(&path)?);
+        } else if path.extension().is_some_and(|ext| ext == "rb") {
+            files.push(path);
+        }
+    }
+
+    Ok(files)
+}
+
+// Matches `get "/posts" do` and `get get_posts_route do`, returning the verb and path.
+fn parse_declaration(line: &str, config_routes: &[Route]) -> Option<(String, String)> {
+    let (verb, rest) = line.split_once(char::is_whitespace)?;
+    if !VERBS.contains(&verb) {
+        return None;
+    }
+
+    let rest = rest.trim_start();
+    let quote = rest.chars().next()?;
+    let path = if quote == '"' || quote == '\'' {
+        rest[1..].split(quote).next()?.to_string()
+    } else {
+        // RouteBuilder defines a *_route for every *_path helper
+        let route = rest.split(|c: char| c.is_whitespace() || c == '(').next()?;
+        let helper = route.strip_suffix("_route")?.to_string() + "_path";
+        config_routes
+            .iter()
+            .find(|r| r.helper.as_ref() == Some(&helper))?
+            .path
+            .clone()
+    };
+
+    Some((verb.to_uppercase(), path))
+}
+
+// Param names differ between templates, `/posts/:id` and `/posts/:post_id` are the same route.
+fn same_path(a: &str, b: &str) -> bool {
+    let normalize = |path: &str| {
+        path.split('/')
+            .map(|segment| if segment.starts_with(':') { ":" } else { segment })
+            .collect::<Vec<&str>>()
+            .join("/")
+    };
+
+    normalize(a) == normalize(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::paths_config::ResourcePath;
+
+    fn resource(name: &str) -> ResourcePath {
+        ResourcePath { name: name.to_string(), alias: None, belongs_to: None, namespace: None }
+    }
+
+    fn config(resources: Vec<ResourcePath>) -> PathsConfig {
+        PathsConfig { resources }
+    }
+
+    fn find<'a>(routes: &'a [Route], verb: &str, path: &str) -> Option<&'a Route> {
+        routes.iter().find(|r| r.verb == verb && r.path == path)
+    }
+
+    #[test]
+    fn config_routes_cover_every_resource_action() {
+        let routes = config_routes(&config(vec![resource("posts")]));
+        let helpers: Vec<Option<&str>> = routes.iter().map(|r| r.helper.as_deref()).collect();
+
+        assert_eq!(routes.len(), 7);
+        assert!(routes.iter().all(|r| r.controller == "PostsController"));
+        assert_eq!(
+            helpers,
+            vec![
+                Some("get_posts_path"),
+                Some("new_post_path"),
+                Some("get_post_path"),
+                Some("edit_post_path"),
+                Some("create_post_path"),
+                Some("update_post_path"),
+                None,
+            ]
+        );
+        assert!(find(&routes, "DELETE", "/posts/:id").is_some());
+    }
+
+    #[test]
+    fn config_routes_use_the_alias_in_paths_only() {
+        let posts = ResourcePath { alias: Some("articles".to_string()), ..resource("posts") };
+        let routes = config_routes(&config(vec![posts]));
+
+        let index = find(&routes, "GET", "/articles").unwrap();
+        assert_eq!(index.helper.as_deref(), Some("get_posts_path"));
+        assert_eq!(index.controller, "PostsController");
+    }
+
+    #[test]
+    fn config_routes_nest_under_the_parent_alias() {
+        let posts = ResourcePath { alias: Some("articles".to_string()), ..resource("posts") };
+        let comments = ResourcePath { belongs_to: Some("posts".to_string()), ..resource("comments") };
+        let routes = config_routes(&config(vec![posts, comments]));
+
+        let index = find(&routes, "GET", "/articles/:article_id/comments").unwrap();
+        assert_eq!(index.helper.as_deref(), Some("get_comments_path"));
+        assert!(find(&routes, "GET", "/comments/:id").is_some());
+    }
+
+    #[test]
+    fn config_routes_for_a_namespaced_controller() {
+        let posts = ResourcePath {
+            alias: Some("posts".to_string()),
+            namespace: Some("admin".to_string()),
+            ..resource("admin_posts")
+        };
+        let routes = config_routes(&config(vec![posts]));
+
+        let index = find(&routes, "GET", "/admin/posts").unwrap();
+        assert_eq!(index.controller, "Admin::PostsController");
+        assert_eq!(index.helper.as_deref(), Some("get_admin_posts_path"));
+        assert_eq!(find(&routes, "GET", "/admin/posts/new").unwrap().helper.as_deref(), Some("new_admin_post_path"));
+    }
+
+    #[test]
+    fn parses_quoted_and_helper_declarations() {
+        let routes = config_routes(&config(vec![resource("posts")]));
+
+        assert_eq!(parse_declaration("get \"/posts\" do", &routes), Some(("GET".to_string(), "/posts".to_string())));
+        assert_eq!(
+            parse_declaration("delete '/posts/:id' do |id|", &routes),
+            Some(("DELETE".to_string(), "/posts/:id".to_string()))
+        );
+        assert_eq!(
+            parse_declaration("get get_post_route do |id|", &routes),
+            Some(("GET".to_string(), "/posts/:id".to_string()))
+        );
+        assert_eq!(parse_declaration("get get_authors_route do", &routes), None);
+        assert_eq!(parse_declaration("getter \"/posts\" do", &routes), None);
+        assert_eq!(parse_declaration("# get \"/posts\" do", &routes), None);
+    }
+
+    #[test]
+    fn paths_match_whatever_the_param_names() {
+        assert!(same_path("/posts/:id/edit", "/posts/:post_id/edit"));
+        assert!(!same_path("/posts/:id", "/posts/new"));
+        assert!(!same_path("/posts/:id", "/posts/:id/edit"));
+    }
+
+    #[test]
+    fn file_routes_use_the_class_name_of_a_namespaced_controller() {
+        let posts = ResourcePath {
+            alias: Some("posts".to_string()),
+            namespace: Some("admin".to_string()),
+            ..resource("admin_posts")
+        };
+        let config_routes = config_routes(&config(vec![posts]));
+        let content = r#"
+module Admin; end
+
+class Admin::PostsController < ApplicationController
+  get "/admin/posts/:post_id" do |id|
+  end
+
+  get "/admin/posts/stats" do
+  end
+end
+"#;
+
+        let routes = file_routes(content, "Posts", &config_routes);
+
+        assert_eq!(routes.len(), 2);
+        assert!(routes.iter().all(|r| r.controller == "Admin::PostsController"));
+        assert_eq!(routes[0].helper.as_deref(), Some("get_admin_post_path"));
+        assert_eq!(routes[1].helper, None);
+    }
+
+    #[test]
+    fn file_routes_fall_back_to_the_filename() {
+        let routes = file_routes("get \"/health\" do\nend\n", "Health", &[]);
+
+        assert_eq!(routes[0].controller, "Health");
+    }
+
+    #[test]
+    fn merge_adds_resources_whose_controller_is_missing() {
+        let config_routes = config_routes(&config(vec![resource("posts"), resource("notes")]));
+        let content = "class PostsController < ApplicationController\n  get \"/posts\" do\n  end\nend\n";
+        let declared = file_routes(content, "Posts", &config_routes);
+
+        let routes = merge(declared, config_routes);
+
+        // the declared posts route, and every notes route from paths_config.toml
+        assert_eq!(routes.iter().filter(|r| r.controller == "PostsController").count(), 1);
+        assert_eq!(routes.iter().filter(|r| r.controller == "NotesController").count(), 7);
+    }
+
+    #[test]
+    fn json_lists_every_field() {
+        let routes = vec![Route {
+            verb: "DELETE".to_string(),
+            path: "/posts/:id".to_string(),
+            controller: "PostsController".to_string(),
+            helper: None,
+        }];
+
+        let json: serde_json::Value = serde_json::from_str(&to_json(&routes).unwrap()).unwrap();
+
+        assert_eq!(
+            json,
+            serde_json::json!([
+                { "verb": "DELETE", "path": "/posts/:id", "controller": "PostsController", "helper": null }
+            ])
+        );
+    }
+}
@@ -0,0 +1,61 @@
+use crate::template_writer::{
+    embedded_template, override_path, project_templates_dir, template_names, user_templates_dir,
+    write_file, WriteMode,
+};
+use colored::Colorize;
+
+pub fn eject(name: &Option<String>, user: bool, write_mode: WriteMode) -> Result<(), String> {
+    let names = match name {
+        Some(name) => {
+            let filename = if name.ends_with(".template") {
+                name.clone()
+            } else {
+                name.clone() + ".template"
+            };
+            if embedded_template(&filename).is_none() {
+                return Err("Unknown template ".to_string() + &filename + ", see `blue-eyes templates list`");
+            }
+            vec![filename]
+        }
+        None => template_names(),
+    };
+
+    let target_dir = if user {
+        match user_templates_dir() {
+            Some(dir) => dir,
+            None => return Err("Unable to find the user config directory".to_string()),
+        }
+    } else {
+        project_templates_dir()
+    };
+
+    for filename in names {
+        if let Some(content) = embedded_template(&filename) {
+            let output_path = target_dir.join(&filename);
+            write_file(&output_path.to_string_lossy(), &content, write_mode)?;
+        }
+    }
+
+    Ok(())
+}
+
+pub fn list() -> Result<(), String> {
+    let project_dir = project_templates_dir();
+
+    for filename in template_names() {
+        match override_path(&filename) {
+            Some(path) if path.starts_with(&project_dir) => {
+                println!("{:<32}{}", filename, "project".green())
+            }
+            Some(path) => println!(
+                "{:<32}{} {}",
+                filename,
+                "user".cyan(),
+                path.to_string_lossy()
+            ),
+            None => println!("{:<32}{}", filename, "embedded".dimmed()),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use std::env;
+use std::fs;
+use std::process::{self, Command};
+use colored::Colorize;
+use tera::Context;
+use crate::boot;
+use crate::environment;
+use crate::template_writer::render;
+
+// Loads boot.rb, the models, services and helpers config.ru sees without the controllers, and opens IRB.
+pub fn start(sandbox: bool) -> Result<(), String> {
+    boot::ensure()?;
+
+    // fail with a readable message before ruby does
+    environment::database_url()?;
+
+    let mut context = Context::new();
+    context.insert("sandbox", &sandbox);
+    let script = render("console.template", &context)?;
+
+    let path = env::temp_dir().join(format!("blue_eyes_console-{}.rb", process::id()));
+    if let Err(e) = fs::write(&path, script) {
+        return Err(path.to_string_lossy().to_string() + ": " + &e.to_string());
+    }
+
+    if sandbox {
+        println!("{}", "Sandbox mode, changes are rolled back when the console exits".yellow());
+    }
+
+    let status = Command::new("bundle")
+        .arg("exec")
+        .arg("ruby")
+        .arg(&path)
+        .status();
+    fs::remove_file(&path).ok();
+
+    match status {
+        Ok(status) if status.success() => Ok(()),
+        Ok(status) => Err(format!("console exited with {}", status)),
+        Err(e) => Err("bundle: ".to_string() + &e.to_string()),
+    }
+}
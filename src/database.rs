@@ -0,0 +1,29 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Database {
+    #[default]
+    Sqlite,
+    Postgres,
+    Mysql,
+}
+
+impl Database {
+    pub fn gem(&self) -> &'static str {
+        match self {
+            Database::Sqlite => "sqlite3",
+            Database::Postgres => "pg",
+            Database::Mysql => "mysql2",
+        }
+    }
+
+    pub fn connection_string(&self, name: &str) -> String {
+        match self {
+            Database::Sqlite => format!("sqlite://{}.db", name),
+            Database::Postgres => format!("postgres://localhost/{}", name),
+            Database::Mysql => format!("mysql2://localhost/{}", name),
+        }
+    }
+}
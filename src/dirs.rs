@@ -6,6 +6,7 @@ pub enum Dir<'a> {
     App(Option<&'a str>),
     Controllers(Option<&'a str>),
     Models(Option<&'a str>),
+    Services(Option<&'a str>),
     Styles(Option<&'a str>),
     Views(Option<&'a str>),
     Bin(Option<&'a str>),
@@ -16,6 +17,7 @@ pub enum Dir<'a> {
     PathsPlugins(Option<&'a str>),
     BundleConfig(Option<&'a str>),
     Helpers(Option<&'a str>),
+    Templates(Option<&'a str>),
     Root(Option<&'a str>),
 }
 
@@ -38,6 +40,7 @@ impl<'a> Dir<'a> {
             Dir::App(filename) => Dir::create_path(vec!["app"], *filename),
             Dir::Controllers(filename) => Dir::create_path(vec!["app", "controllers"], *filename),
             Dir::Models(filename) => Dir::create_path(vec!["app", "models"], *filename),
+            Dir::Services(filename) => Dir::create_path(vec!["app", "services"], *filename),
             Dir::Styles(filename) => Dir::create_path(vec!["app", "styles"], *filename),
             Dir::Views(filename) => Dir::create_path(vec!["app", "views"], *filename),
             Dir::Bin(filename) => Dir::create_path(vec!["bin"], *filename),
@@ -50,6 +53,7 @@ impl<'a> Dir<'a> {
             Dir::PathsPlugins(filename) => Dir::create_path(vec!["plugins", "paths"], *filename),
             Dir::BundleConfig(filename) => Dir::create_path(vec![".bundle"], *filename),
             Dir::Helpers(filename) => Dir::create_path(vec!["helpers"], *filename),
+            Dir::Templates(filename) => Dir::create_path(vec![".blue_eyes", "templates"], *filename),
         };
 
         joined_path.to_str().unwrap().to_string()
@@ -0,0 +1,51 @@
+use std::env;
+use clap::ValueEnum;
+
+// Read by Sinatra as well, so the app and the cli agree on the environment
+pub const APP_ENV: &str = "APP_ENV";
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum Environment {
+    Development,
+    Test,
+    Production,
+}
+
+impl Environment {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Environment::Development => "development",
+            Environment::Test => "test",
+            Environment::Production => "production",
+        }
+    }
+}
+
+pub fn select(environment: Option<Environment>) {
+    if let Some(environment) = environment {
+        env::set_var(APP_ENV, environment.as_str());
+    }
+}
+
+pub fn current() -> String {
+    env::var(APP_ENV).unwrap_or(Environment::Development.as_str().to_string())
+}
+
+// Values in .env.<env> win over the shared .env, variables already set in the shell win over both.
+pub fn load() {
+    dotenvy::from_filename(format!(".env.{}", current())).ok();
+    dotenvy::from_filename(".env").ok();
+}
+
+pub fn database_url() -> Result<String, String> {
+    load();
+
+    match env::var("DATABASE_URL") {
+        Ok(url) if !url.trim().is_empty() => Ok(url),
+        _ => Err(format!(
+            "DATABASE_URL is not set for the {} environment, add it to .env.{} or .env",
+            current(),
+            current()
+        )),
+    }
+}
@@ -0,0 +1,148 @@
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+use inflector::Inflector;
+use serde::{Deserialize, Serialize};
+use tera::Context;
+use crate::dirs::Dir;
+use crate::template_writer::{render, update_file, write_file, WriteMode};
+
+// Rules from the project manifest, installed once the project root is found
+static RULES: OnceLock<Inflections> = OnceLock::new();
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Inflections {
+    // singular = "plural"
+    #[serde(default)]
+    pub irregular: BTreeMap<String, String>,
+
+    #[serde(default)]
+    pub uncountable: Vec<String>,
+}
+
+impl Inflections {
+    // Writes helpers/inflections.rb so ActiveSupport and Sequel inflect like the generator.
+    pub fn export(&self, mode: WriteMode) -> Result<bool, String> {
+        let mut context = Context::new();
+        context.insert("irregular", &self.irregular);
+        context.insert("uncountable", &self.uncountable);
+
+        let content = render("inflections.template", &context)?;
+        let path = Dir::Helpers(Some("inflections.rb")).path();
+
+        // --skip keeps a hand-edited file, write_file reports it instead of updating it
+        match mode {
+            WriteMode::Skip => write_file(&path, content.as_bytes(), mode),
+            _ => update_file(&path, &content, mode),
+        }
+    }
+
+    fn pluralize(&self, word: &str) -> Option<String> {
+        if self.uncountable.iter().any(|w| w == word) || self.irregular.values().any(|w| w == word) {
+            return Some(word.to_string());
+        }
+
+        self.irregular.get(word).cloned()
+    }
+
+    fn singularize(&self, word: &str) -> Option<String> {
+        if self.uncountable.iter().any(|w| w == word) || self.irregular.contains_key(word) {
+            return Some(word.to_string());
+        }
+
+        self.irregular
+            .iter()
+            .find(|(_, plural)| plural.as_str() == word)
+            .map(|(singular, _)| singular.clone())
+    }
+}
+
+pub fn install(rules: Inflections) {
+    let _ = RULES.set(rules);
+}
+
+pub fn pluralize(word: &str) -> String {
+    pluralize_with(RULES.get(), word)
+}
+
+pub fn singularize(word: &str) -> String {
+    singularize_with(RULES.get(), word)
+}
+
+// Only the last word of a snake_case name is inflected, blog_person becomes blog_people.
+fn pluralize_with(rules: Option<&Inflections>, word: &str) -> String {
+    inflect(rules, word, |rules, last| rules.pluralize(last)).unwrap_or_else(|| word.to_plural())
+}
+
+fn singularize_with(rules: Option<&Inflections>, word: &str) -> String {
+    inflect(rules, word, |rules, last| rules.singularize(last)).unwrap_or_else(|| word.to_singular())
+}
+
+fn inflect(
+    rules: Option<&Inflections>,
+    word: &str,
+    rule: impl Fn(&Inflections, &str) -> Option<String>,
+) -> Option<String> {
+    let rules = rules?;
+    let (head, last) = match word.rsplit_once('_') {
+        Some((head, last)) => (head.to_string() + "_", last),
+        None => (String::new(), word),
+    };
+
+    rule(rules, &last.to_lowercase()).map(|inflected| head + &inflected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules() -> Inflections {
+        Inflections {
+            irregular: BTreeMap::from([("person".to_string(), "people".to_string())]),
+            uncountable: vec!["data".to_string()],
+        }
+    }
+
+    #[test]
+    fn irregular_words() {
+        let rules = rules();
+
+        assert_eq!(rules.pluralize("person"), Some("people".to_string()));
+        assert_eq!(rules.pluralize("people"), Some("people".to_string()));
+        assert_eq!(rules.singularize("people"), Some("person".to_string()));
+        assert_eq!(rules.singularize("person"), Some("person".to_string()));
+    }
+
+    #[test]
+    fn uncountable_words() {
+        let rules = rules();
+
+        assert_eq!(rules.pluralize("data"), Some("data".to_string()));
+        assert_eq!(rules.singularize("data"), Some("data".to_string()));
+    }
+
+    #[test]
+    fn other_words_are_left_to_the_defaults() {
+        let rules = rules();
+
+        assert_eq!(rules.pluralize("post"), None);
+        assert_eq!(rules.singularize("posts"), None);
+    }
+
+    #[test]
+    fn inflects_the_last_word_of_a_snake_case_name() {
+        let rules = rules();
+
+        assert_eq!(pluralize_with(Some(&rules), "blog_person"), "blog_people");
+        assert_eq!(singularize_with(Some(&rules), "blog_people"), "blog_person");
+        assert_eq!(pluralize_with(Some(&rules), "sensor_data"), "sensor_data");
+        assert_eq!(pluralize_with(Some(&rules), "blog_post"), "blog_posts");
+        assert_eq!(singularize_with(Some(&rules), "blog_posts"), "blog_post");
+    }
+
+    #[test]
+    fn falls_back_to_the_default_rules() {
+        assert_eq!(pluralize_with(None, "blog_category"), "blog_categories");
+        assert_eq!(pluralize_with(None, "post"), "posts");
+        assert_eq!(singularize_with(None, "posts"), "post");
+    }
+}
@@ -1,3 +1,4 @@
+use std::process;
 use clap::Parser;
 use colored::Colorize;
 use env_logger::Env;
@@ -8,30 +9,139 @@ mod dirs;
 mod utils;
 mod template_writer;
 mod migrate;
+mod tailwind;
+mod manifest;
+mod database;
+mod db;
+mod environment;
+mod paths_config;
+mod inflections;
+mod console;
+mod server;
+mod boot;
+mod runner;
 
-use crate::cli_commands::cli::{Cli, CommandType, Commands};
+use crate::cli_commands::cli::{
+    Cli, CommandType, Commands, DbSubcommand, DestroySubcommand, MigrateSubcommand, NewArgs, SetupArgs,
+    TemplatesSubcommand,
+};
+use crate::cli_commands::migration::Migration;
 use crate::cli_commands::resource::Resource;
 use cli_commands::cli::GenerateSubcommand;
 use cli_commands::project::Project;
+use cli_commands::routes;
+use cli_commands::templates;
+use manifest::Manifest;
+use server::Server;
 
 fn handle_generate(entity: &GenerateSubcommand) -> Result<(), String> {
+    let manifest = Manifest::enter_project()?;
+
+    // names and fields are validated before anything in the project changes
+    let resource = match entity {
+        GenerateSubcommand::Api(args) => Resource::new(args, CommandType::Api)?,
+        GenerateSubcommand::Controller(args) => Resource::new(args, CommandType::Controller)?,
+        GenerateSubcommand::Model(args) => Resource::new(args, CommandType::Model)?,
+        GenerateSubcommand::Scaffold(args) => Resource::new(args, CommandType::Scaffold)?,
+        GenerateSubcommand::Service(args) => Resource::service(args)?,
+        GenerateSubcommand::Migration { entity: migration } => {
+            let migration = Migration::new(migration)?;
+            manifest.inflections.export(entity.write_mode())?;
+            return migration.generate_template();
+        }
+    };
+
+    // keep the app's inflections in sync with the rules used to name the generated files
+    manifest.inflections.export(entity.write_mode())?;
+
+    resource.generate_template()
+}
+
+fn handle_destroy(entity: &DestroySubcommand) -> Result<(), String> {
+    Manifest::enter_project()?;
+
     let resource = match entity {
-        GenerateSubcommand::Api(args) => Resource::new(args, CommandType::Api),
-        GenerateSubcommand::Controller(args) => Resource::new(args, CommandType::Controller),
-        GenerateSubcommand::Model(args) => Resource::new(args, CommandType::Model),
-        GenerateSubcommand::Scaffold(args) => Resource::new(args, CommandType::Scaffold),
-        //GenerateSubcommand::Migration{entity} => Res 
-        _ => return Err("Not implemented".to_string())
+        DestroySubcommand::Api(args) => Resource::named(&args.name, CommandType::Api)?,
+        DestroySubcommand::Controller(args) => Resource::named(&args.name, CommandType::Controller)?,
+        DestroySubcommand::Model(args) => Resource::named(&args.name, CommandType::Model)?,
+        DestroySubcommand::Scaffold(args) => Resource::named(&args.name, CommandType::Scaffold)?,
+        DestroySubcommand::Service(args) => Resource::named(&args.name, CommandType::Service)?,
     };
 
-    match resource.generate_template() {
-        Ok(_) => Ok(()),
-        Err(e) => Err(e), 
+    resource.destroy_template()
+}
+
+fn handle_templates(action: &TemplatesSubcommand) -> Result<(), String> {
+    match action {
+        TemplatesSubcommand::Eject { name, user, write } => {
+            if !*user {
+                Manifest::enter_project()?;
+            }
+            templates::eject(name, *user, write.mode())
+        }
+        TemplatesSubcommand::List => {
+            // project overrides are only listed from inside a project
+            if Manifest::find_root().is_some() {
+                Manifest::enter_project()?;
+            }
+            templates::list()
+        }
     }
 }
 
-fn handle_new(project_name: String, db: String) -> Result<(), String> {
-    let mut project = Project::new(project_name, db);
+fn handle_migrate(to: &Option<i64>, action: &Option<MigrateSubcommand>) -> Result<(), String> {
+    Manifest::enter_project()?;
+
+    match action {
+        Some(MigrateSubcommand::Rollback { steps }) => migrate::rollback(*steps),
+        Some(MigrateSubcommand::Redo) => migrate::redo(),
+        Some(MigrateSubcommand::Status) => migrate::status(),
+        None => migrate::run_to(*to),
+    }
+}
+
+fn handle_db(action: &DbSubcommand) -> Result<(), String> {
+    Manifest::enter_project()?;
+
+    match action {
+        DbSubcommand::Create => db::create(),
+        DbSubcommand::Drop => db::drop(),
+        DbSubcommand::Reset => db::reset(),
+        DbSubcommand::Seed => db::seed(),
+    }
+}
+
+fn handle_routes(json: bool) -> Result<(), String> {
+    Manifest::enter_project()?;
+    routes::list(json)
+}
+
+fn handle_console(sandbox: bool) -> Result<(), String> {
+    Manifest::enter_project()?;
+    console::start(sandbox)
+}
+
+fn handle_server(port: u16, host: &str) -> Result<(), String> {
+    Manifest::enter_project()?;
+    Server::new(port, host).start()
+}
+
+fn handle_runner(code: &str, args: &[String]) -> Result<(), String> {
+    // a script path is relative to where the command was run, not the project root
+    let code = match std::fs::canonicalize(code) {
+        Ok(path) if path.is_file() => path.to_string_lossy().to_string(),
+        _ => code.to_string(),
+    };
+
+    Manifest::enter_project()?;
+    match runner::run(&code, args)? {
+        0 => Ok(()),
+        exit_code => process::exit(exit_code),
+    }
+}
+
+fn handle_new(args: &NewArgs) -> Result<(), String> {
+    let mut project = Project::new(args);
     if let Err(e) = project.generate() {
         println!("Failed to generate project {}", &e.red());
         return Err(e.to_string());
@@ -40,22 +150,43 @@ fn handle_new(project_name: String, db: String) -> Result<(), String> {
     Ok(())
 }
 
+fn handle_setup(args: &SetupArgs) -> Result<(), String> {
+    let manifest = Manifest::enter_project()?;
+    let project = Project::for_setup(args, &manifest)?;
+    project.setup()
+}
+
 fn main() {
     env_logger::Builder::from_env(Env::default().default_filter_or("info")).init();
 
     let cli = Cli::parse();
+    environment::select(cli.env);
 
     let result = match &cli.command {
-        Commands::New { project_name, db } => {
-            handle_new(String::from(project_name), String::from(db))
-        }
-        Commands::Migrate => migrate::run(),
+        Commands::New(args) => handle_new(args),
+        Commands::Setup(args) => handle_setup(args),
+        Commands::Migrate { to, action } => handle_migrate(to, action),
+        Commands::Db { action } => handle_db(action),
+        Commands::Routes { json } => handle_routes(*json),
+        Commands::Server { port, host } => handle_server(*port, host),
+        Commands::Runner { code, args } => handle_runner(code, args),
+        Commands::Console { sandbox } => handle_console(*sandbox),
         Commands::Generate { entity } => handle_generate(entity),
         Commands::G { entity } => handle_generate(entity),
+        Commands::Destroy { entity } => handle_destroy(entity),
+        Commands::Templates { action } => handle_templates(action),
     };
 
+    // machine readable output must stay parseable
+    let quiet = matches!(cli.command, Commands::Routes { json: true } | Commands::Runner { .. });
+
     match result {
+        Ok(()) if quiet => {}
         Ok(()) => println!("{}", "Complete".green().bold()),
-        Err(e) => println!("Error: {}", e.red().bold())
+        Err(e) => {
+            // cron jobs and CI rely on the exit code to notice a failure
+            eprintln!("Error: {}", e.red().bold());
+            process::exit(1);
+        }
     }
 }
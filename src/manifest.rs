@@ -0,0 +1,94 @@
+use std::env::{self, current_dir};
+use std::fs;
+use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use crate::database::Database;
+use crate::dirs::Dir;
+use crate::inflections::{self, Inflections};
+use crate::template_writer::{write_file, WriteMode};
+
+pub const MANIFEST_FILE: &str = "blue_eyes.toml";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Manifest {
+    pub project: ProjectInfo,
+
+    #[serde(default)]
+    pub inflections: Inflections,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectInfo {
+    pub name: String,
+    pub generator_version: String,
+    pub db: Database,
+    pub tailwind_version: String,
+    pub templates: String,
+}
+
+impl Manifest {
+    pub fn new(name: &str, db: Database, tailwind_version: &str) -> Self {
+        Manifest {
+            project: ProjectInfo {
+                name: name.to_string(),
+                generator_version: env!("CARGO_PKG_VERSION").to_string(),
+                db,
+                tailwind_version: tailwind_version.to_string(),
+                templates: "default".to_string(),
+            },
+            inflections: Inflections::default(),
+        }
+    }
+
+    // Moves into the project root so every `Dir` path resolves from there.
+    pub fn enter_project() -> Result<Self, String> {
+        let root = match Manifest::find_root() {
+            Some(root) => root,
+            None => {
+                return Err(format!(
+                    "Not inside a blue-eyes project, no {} found in this or any parent directory",
+                    MANIFEST_FILE
+                ))
+            }
+        };
+
+        if let Err(e) = env::set_current_dir(&root) {
+            return Err(e.to_string());
+        }
+
+        let manifest = Manifest::load()?;
+        inflections::install(manifest.inflections.clone());
+
+        Ok(manifest)
+    }
+
+    pub fn find_root() -> Option<PathBuf> {
+        let start = current_dir().ok()?;
+
+        start
+            .ancestors()
+            .find(|dir| dir.join(MANIFEST_FILE).is_file())
+            .map(|dir| dir.to_path_buf())
+    }
+
+    pub fn load() -> Result<Self, String> {
+        let content = match fs::read_to_string(Dir::Root(Some(MANIFEST_FILE)).path()) {
+            Ok(content) => content,
+            Err(e) => return Err(MANIFEST_FILE.to_string() + ": " + &e.to_string()),
+        };
+
+        match toml::from_str(&content) {
+            Ok(manifest) => Ok(manifest),
+            Err(e) => Err(MANIFEST_FILE.to_string() + ": " + &e.to_string()),
+        }
+    }
+
+    pub fn write(&self, mode: WriteMode) -> Result<bool, String> {
+        let content = match toml::to_string(self) {
+            Ok(content) => content,
+            Err(e) => return Err(e.to_string()),
+        };
+
+        write_file(&Dir::Root(Some(MANIFEST_FILE)).path(), content.as_bytes(), mode)
+    }
+}
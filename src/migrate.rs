@@ -1,24 +1,186 @@
-use std::env;
+use std::fs;
 use std::process::Command;
-use dotenvy::dotenv;
+use colored::Colorize;
 use crate::dirs::Dir;
+use crate::environment;
 
 pub fn run () -> Result<(), String> {
-    dotenv().ok();
-    let connection_string = env::var("DATABASE_URL").expect("DATABASE_URL is not set in .env file");
+    run_to(None)
+}
 
-    let cmd = Command::new("bundle")
-        .arg("exec")
+pub fn run_to(version: Option<i64>) -> Result<(), String> {
+    let connection_string = environment::database_url()?;
+
+    let mut cmd = Command::new("bundle");
+    cmd.arg("exec")
         .arg("sequel")
         .arg("-m")
-        .arg(Dir::Migrations(None).path())
-        .arg(connection_string.clone())
+        .arg(Dir::Migrations(None).path());
+
+    if let Some(version) = version {
+        cmd.arg("-M").arg(version.to_string());
+    }
+
+    let output = cmd
+        .arg(connection_string)
         .output()
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| format!("bundle exec sequel: {}", e))?;
+
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).to_string());
+    }
+
+    Ok(())
+}
+
+pub fn rollback(steps: usize) -> Result<(), String> {
+    let applied = applied_migrations()?;
+    if applied.is_empty() {
+        return Err("No migrations have been applied".to_string());
+    }
+
+    // migrating to the version of the last migration we keep reverts everything after it
+    let target = if steps >= applied.len() {
+        0
+    } else {
+        version(&applied[applied.len() - steps - 1])?
+    };
+    check_pending(&applied, target)?;
+
+    for filename in applied.iter().rev().take(steps) {
+        println!("{} {}", "Reverting".yellow(), filename.yellow().bold());
+    }
+
+    run_to(Some(target))
+}
+
+// Down one and back up to the same version, pending migrations after it stay pending
+pub fn redo() -> Result<(), String> {
+    let applied = applied_migrations()?;
+    let last = match applied.last() {
+        Some(last) => version(last)?,
+        None => return Err("No migrations have been applied".to_string()),
+    };
+    check_pending(&applied, last)?;
+
+    rollback(1)?;
+    run_to(Some(last))
+}
+
+// The migrator applies every pending migration at or below the target, even when it migrates down.
+fn check_pending(applied: &[String], target: i64) -> Result<(), String> {
+    let pending = pending_at_or_below(&migration_files()?, applied, target);
+    if pending.is_empty() {
+        return Ok(());
+    }
+
+    Err(format!(
+        "Migrating to {} would also apply the pending {}, apply them with `blue-eyes migrate` or remove them first",
+        target,
+        pending.join(", ")
+    ))
+}
+
+fn pending_at_or_below(files: &[String], applied: &[String], target: i64) -> Vec<String> {
+    files
+        .iter()
+        .filter(|filename| !applied.contains(filename))
+        .filter(|filename| version(filename).is_ok_and(|version| version <= target))
+        .cloned()
+        .collect()
+}
+
+pub fn status() -> Result<(), String> {
+    let applied = applied_migrations()?;
+    let files = migration_files()?;
 
-    if !cmd.status.success() {
-        return Err(String::from_utf8(cmd.stderr).unwrap());
+    let mut all: Vec<&String> = files.iter().chain(applied.iter()).collect();
+    all.sort_by_key(|filename| version(filename).unwrap_or(0));
+    all.dedup();
+
+    println!("{:<8}Migration", "Status");
+    println!("--------------------------------------------------");
+    for filename in all {
+        match (applied.contains(filename), files.contains(filename)) {
+            (true, true) => println!("{:<8}{}", "up".green(), filename),
+            (true, false) => println!("{:<8}{} {}", "up".green(), filename, "(NO FILE)".red()),
+            _ => println!("{:<8}{}", "down".yellow(), filename),
+        }
     }
 
     Ok(())
-}
\ No newline at end of file
+}
+
+pub fn applied_migrations() -> Result<Vec<String>, String> {
+    let script = "puts DB[:schema_migrations].select_order_map(:filename) if DB.table_exists?(:schema_migrations)";
+
+    let output = Command::new("bundle")
+        .arg("exec")
+        .arg("sequel")
+        .arg("-c")
+        .arg(script)
+        .arg(environment::database_url()?)
+        .output()
+        .map_err(|e| format!("bundle exec sequel: {}", e))?;
+
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).to_string());
+    }
+
+    let stdout = String::from_utf8(output.stdout).map_err(|e| e.to_string())?;
+    let mut applied: Vec<String> = stdout
+        .lines()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect();
+    applied.sort_by_key(|filename| version(filename).unwrap_or(0));
+
+    Ok(applied)
+}
+
+fn migration_files() -> Result<Vec<String>, String> {
+    let entries = fs::read_dir(Dir::Migrations(None).path()).map_err(|e| e.to_string())?;
+
+    let mut files: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|filename| filename.ends_with(".rb"))
+        .collect();
+    files.sort_by_key(|filename| version(filename).unwrap_or(0));
+
+    Ok(files)
+}
+
+fn version(filename: &str) -> Result<i64, String> {
+    filename
+        .split('_')
+        .next()
+        .and_then(|v| v.parse::<i64>().ok())
+        .ok_or(format!("Migration {} does not start with a timestamp", filename))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(filenames: &[&str]) -> Vec<String> {
+        filenames.iter().map(|filename| filename.to_string()).collect()
+    }
+
+    #[test]
+    fn finds_pending_migrations_at_or_below_the_target() {
+        let files = names(&["100_create_users.rb", "200_create_posts.rb", "300_create_notes.rb", "400_create_tags.rb"]);
+        let applied = names(&["100_create_users.rb", "300_create_notes.rb"]);
+
+        assert_eq!(pending_at_or_below(&files, &applied, 300), names(&["200_create_posts.rb"]));
+        assert_eq!(pending_at_or_below(&files, &applied, 200), names(&["200_create_posts.rb"]));
+        assert!(pending_at_or_below(&files, &applied, 100).is_empty());
+        assert!(pending_at_or_below(&files, &applied, 0).is_empty());
+    }
+
+    #[test]
+    fn reads_the_version_from_the_filename() {
+        assert_eq!(version("1712966207_create_users.rb"), Ok(1712966207));
+        assert!(version("create_users.rb").is_err());
+    }
+}
@@ -0,0 +1,210 @@
+use std::fs;
+use serde::{Deserialize, Serialize};
+use crate::dirs::Dir;
+use crate::template_writer::{update_file, WriteMode};
+
+// helpers/paths_config.toml, read by PathsHelper to define the *_path and *_route helpers
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PathsConfig {
+    #[serde(default)]
+    pub resources: Vec<ResourcePath>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResourcePath {
+    pub name: String,
+
+    #[serde(rename = "as", default, skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub belongs_to: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+}
+
+impl ResourcePath {
+    // The url segment RouteBuilder uses for the resource
+    pub fn path(&self) -> &str {
+        self.alias.as_deref().unwrap_or(&self.name)
+    }
+
+    // The path including the namespace prefix, e.g. admin/posts
+    pub fn full_path(&self) -> String {
+        match &self.namespace {
+            Some(namespace) => namespace.clone() + "/" + self.path(),
+            None => self.path().to_string(),
+        }
+    }
+}
+
+impl PathsConfig {
+    pub fn file() -> String {
+        Dir::Helpers(Some("paths_config.toml")).path()
+    }
+
+    pub fn load() -> Result<Self, String> {
+        let content = match fs::read_to_string(PathsConfig::file()) {
+            Ok(content) => content,
+            Err(_) => return Ok(PathsConfig::default()),
+        };
+
+        let parsed: PathsConfig = match toml::from_str(&content) {
+            Ok(parsed) => parsed,
+            Err(e) => return Err(PathsConfig::file() + ": " + &e.to_string()),
+        };
+
+        // older generators appended a block per run, the last entry for a name wins
+        let mut config = PathsConfig::default();
+        for mut resource in parsed.resources {
+            resource.alias = resource.alias.filter(|alias| !alias.is_empty());
+            resource.belongs_to = resource.belongs_to.filter(|belongs_to| !belongs_to.is_empty());
+            resource.namespace = resource.namespace.filter(|namespace| !namespace.is_empty());
+            config.upsert(resource);
+        }
+
+        Ok(config)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&ResourcePath> {
+        self.resources.iter().find(|resource| resource.name == name)
+    }
+
+    pub fn upsert(&mut self, resource: ResourcePath) {
+        match self.resources.iter_mut().find(|existing| existing.name == resource.name) {
+            Some(existing) => *existing = resource,
+            None => self.resources.push(resource),
+        }
+    }
+
+    pub fn remove(&mut self, name: &str) -> bool {
+        let count = self.resources.len();
+        self.resources.retain(|resource| resource.name != name);
+
+        count != self.resources.len()
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        for resource in &self.resources {
+            if let Some(belongs_to) = &resource.belongs_to {
+                if self.get(belongs_to).is_none() {
+                    return Err(format!(
+                        "{} belongs to {}, which is not a registered resource, generate a controller for it first",
+                        resource.name, belongs_to
+                    ));
+                }
+            }
+
+            let collision = self
+                .resources
+                .iter()
+                .find(|other| other.name != resource.name && other.full_path() == resource.full_path());
+            if let Some(other) = collision {
+                return Err(format!(
+                    "{} and {} would both route to /{}, choose a different --alias",
+                    other.name,
+                    resource.name,
+                    resource.full_path()
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    // Written sorted by name so regenerating never reorders or duplicates entries.
+    pub fn save(&self, mode: WriteMode) -> Result<bool, String> {
+        self.validate()?;
+
+        let mut sorted = self.clone();
+        sorted.resources.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let content = match toml::to_string(&sorted) {
+            Ok(content) => content,
+            Err(e) => return Err(e.to_string()),
+        };
+
+        update_file(&PathsConfig::file(), &content, mode)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resource(name: &str) -> ResourcePath {
+        ResourcePath { name: name.to_string(), alias: None, belongs_to: None, namespace: None }
+    }
+
+    #[test]
+    fn full_path_uses_alias_and_namespace() {
+        let mut post = resource("posts");
+        assert_eq!(post.full_path(), "posts");
+
+        post.alias = Some("articles".to_string());
+        post.namespace = Some("admin".to_string());
+        assert_eq!(post.full_path(), "admin/articles");
+    }
+
+    #[test]
+    fn upsert_replaces_an_entry_with_the_same_name() {
+        let mut config = PathsConfig::default();
+        config.upsert(resource("posts"));
+        config.upsert(ResourcePath { alias: Some("articles".to_string()), ..resource("posts") });
+
+        assert_eq!(config.resources.len(), 1);
+        assert_eq!(config.get("posts").unwrap().path(), "articles");
+    }
+
+    #[test]
+    fn remove_reports_whether_an_entry_was_removed() {
+        let mut config = PathsConfig::default();
+        config.upsert(resource("posts"));
+
+        assert!(config.remove("posts"));
+        assert!(!config.remove("posts"));
+        assert!(config.resources.is_empty());
+    }
+
+    #[test]
+    fn validate_accepts_a_registered_parent() {
+        let mut config = PathsConfig::default();
+        config.upsert(resource("users"));
+        config.upsert(ResourcePath { belongs_to: Some("users".to_string()), ..resource("posts") });
+
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn validate_rejects_an_unknown_parent() {
+        let mut config = PathsConfig::default();
+        config.upsert(ResourcePath { belongs_to: Some("users".to_string()), ..resource("posts") });
+
+        let err = config.validate().unwrap_err();
+        assert!(err.contains("posts belongs to users"));
+    }
+
+    #[test]
+    fn validate_rejects_two_resources_on_one_path() {
+        let mut config = PathsConfig::default();
+        config.upsert(resource("posts"));
+        config.upsert(ResourcePath { alias: Some("posts".to_string()), ..resource("articles") });
+
+        let err = config.validate().unwrap_err();
+        assert!(err.contains("would both route to /posts"));
+    }
+
+    #[test]
+    fn validate_allows_the_same_path_in_another_namespace() {
+        let mut config = PathsConfig::default();
+        config.upsert(resource("posts"));
+        config.upsert(ResourcePath {
+            alias: Some("posts".to_string()),
+            namespace: Some("admin".to_string()),
+            ..resource("admin_posts")
+        });
+
+        assert!(config.validate().is_ok());
+    }
+}
@@ -0,0 +1,28 @@
+use std::path::Path;
+use std::process::Command;
+use crate::boot;
+use crate::environment;
+
+// Runs a script file, or a snippet of ruby, after boot.rb has connected the DB and loaded the models.
+// Returns the exit code of the ruby process.
+pub fn run(code: &str, args: &[String]) -> Result<i32, String> {
+    boot::ensure()?;
+    environment::database_url()?;
+
+    let mut cmd = Command::new("bundle");
+    cmd.arg("exec").arg("ruby").arg("-r").arg(boot::path());
+
+    if Path::new(code).is_file() {
+        cmd.arg(code);
+    } else if code.ends_with(".rb") {
+        return Err(code.to_string() + " not found");
+    } else {
+        cmd.arg("-e").arg(code);
+    }
+
+    match cmd.args(args).status() {
+        // killed by a signal has no code
+        Ok(status) => Ok(status.code().unwrap_or(1)),
+        Err(e) => Err("bundle: ".to_string() + &e.to_string()),
+    }
+}
@@ -0,0 +1,193 @@
+use std::path::Path;
+use std::process::{Child, ChildStdin, Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{sleep, JoinHandle};
+use std::time::{Duration, Instant};
+use colored::{Color, Colorize};
+use crate::dirs::Dir;
+use crate::environment;
+use crate::utils::stream::forward;
+
+// How long the children get to shut down after Ctrl-C before they are killed
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub struct Server {
+    pub port: u16,
+    pub host: String,
+}
+
+struct Process {
+    name: &'static str,
+    child: Child,
+    // held open, the tailwind watcher exits when its stdin closes
+    _stdin: Option<ChildStdin>,
+    output: Vec<JoinHandle<()>>,
+    status: Option<ExitStatus>,
+}
+
+impl Server {
+    pub fn new(port: u16, host: &str) -> Self {
+        Server { port, host: host.to_string() }
+    }
+
+    // Runs rackup and the tailwind watcher side by side, the way bin/dev does with foreman.
+    pub fn start(&self) -> Result<(), String> {
+        environment::database_url()?;
+
+        let interrupted = Arc::new(AtomicBool::new(false));
+        let flag = interrupted.clone();
+        if let Err(e) = ctrlc::set_handler(move || flag.store(true, Ordering::SeqCst)) {
+            return Err(e.to_string());
+        }
+
+        println!(
+            "{} {} {}",
+            "Starting".green(),
+            format!("http://{}:{}", self.host, self.port).green().bold(),
+            format!("({})", environment::current()).green()
+        );
+
+        let mut processes = vec![self.spawn("web", Color::Cyan, self.rackup())?];
+
+        let tailwind = Dir::Bin(Some("tailwindcss")).path();
+        if Path::new(&tailwind).is_file() {
+            match self.spawn("css", Color::Magenta, self.tailwind(&tailwind)) {
+                Ok(process) => processes.push(process),
+                Err(e) => {
+                    stop(&mut processes);
+                    return Err(e);
+                }
+            }
+        } else {
+            println!("{} {}", tailwind.yellow(), "not found, run blue-eyes setup to watch styles".yellow());
+        }
+
+        // stop everything as soon as Ctrl-C is pressed or one of the processes exits
+        loop {
+            if interrupted.load(Ordering::SeqCst) {
+                break;
+            }
+
+            let mut exited = false;
+            for process in processes.iter_mut() {
+                if let Ok(Some(status)) = process.child.try_wait() {
+                    process.status = Some(status);
+                    exited = true;
+                }
+            }
+            if exited {
+                break;
+            }
+
+            sleep(Duration::from_millis(100));
+        }
+
+        stop(&mut processes);
+
+        if interrupted.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        for process in &processes {
+            match process.status {
+                Some(status) if !status.success() => {
+                    return Err(format!("{} exited with {}", process.name, status))
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    fn rackup(&self) -> Command {
+        let mut cmd = Command::new("bundle");
+        cmd.arg("exec")
+            .arg("rackup")
+            .arg("--port")
+            .arg(self.port.to_string())
+            .arg("--host")
+            .arg(&self.host);
+
+        cmd
+    }
+
+    fn tailwind(&self, binary: &str) -> Command {
+        let mut cmd = Command::new(binary);
+        cmd.arg("-i")
+            .arg(Dir::Styles(Some("application.tailwind.css")).path())
+            .arg("-o")
+            .arg(Dir::Stylesheets(Some("application.css")).path())
+            .arg("--watch");
+
+        cmd
+    }
+
+    fn spawn(&self, name: &'static str, color: Color, mut cmd: Command) -> Result<Process, String> {
+        cmd.stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::piped());
+
+        // in their own process group the children only see the Ctrl-C we forward, and see it once
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::CommandExt;
+            cmd.process_group(0);
+        }
+
+        let mut child = match cmd.spawn() {
+            Ok(child) => child,
+            Err(e) => return Err(name.to_string() + ": " + &e.to_string()),
+        };
+
+        let prefix = format!("{:<5}|", name).color(color).bold().to_string();
+        let mut output = vec![];
+        if let Some(stdout) = child.stdout.take() {
+            let prefix = prefix.clone();
+            output.push(forward(stdout, move |line| println!("{} {}", prefix, line)));
+        }
+        if let Some(stderr) = child.stderr.take() {
+            output.push(forward(stderr, move |line| println!("{} {}", prefix, line)));
+        }
+
+        Ok(Process { name, _stdin: child.stdin.take(), child, output, status: None })
+    }
+}
+
+fn stop(processes: &mut [Process]) {
+    for process in processes.iter_mut().filter(|p| p.status.is_none()) {
+        interrupt(&mut process.child);
+    }
+
+    let deadline = Instant::now() + SHUTDOWN_TIMEOUT;
+    for process in processes.iter_mut() {
+        while process.status.is_none() {
+            match process.child.try_wait() {
+                Ok(Some(status)) => process.status = Some(status),
+                Ok(None) if Instant::now() < deadline => sleep(Duration::from_millis(100)),
+                _ => {
+                    println!("{} {}", process.name.yellow(), "did not stop in time, killing it".yellow());
+                    process.child.kill().ok();
+                    process.status = process.child.wait().ok();
+                    break;
+                }
+            }
+        }
+
+        for handle in process.output.drain(..) {
+            handle.join().ok();
+        }
+    }
+}
+
+#[cfg(unix)]
+fn interrupt(child: &mut Child) {
+    // SIGINT lets puma and the tailwind watcher shut down gracefully
+    unsafe {
+        libc::kill(child.id() as libc::pid_t, libc::SIGINT);
+    }
+}
+
+#[cfg(not(unix))]
+fn interrupt(child: &mut Child) {
+    child.kill().ok();
+}
@@ -0,0 +1,238 @@
+use std::env;
+use std::env::consts::{ARCH, OS};
+use std::fs::{self, create_dir_all};
+use std::path::PathBuf;
+use colored::Colorize;
+use crate::utils::fget::download_file;
+
+const RELEASES_URL: &str = "https://github.com/tailwindlabs/tailwindcss/releases";
+pub const DEFAULT_VERSION: &str = "v3.4.17";
+
+// Path to a tailwindcss binary to use instead of downloading one
+pub const BINARY_ENV: &str = "BLUE_EYES_TAILWIND_BIN";
+// Base url to download releases from instead of GitHub
+pub const MIRROR_ENV: &str = "BLUE_EYES_TAILWIND_MIRROR";
+
+pub struct Tailwind {
+    pub version: String,
+}
+
+impl Tailwind {
+    pub fn new(version: &str) -> Self {
+        let version = match version {
+            "latest" => version.to_string(),
+            v if v.starts_with('v') => v.to_string(),
+            v => "v".to_string() + v,
+        };
+
+        Tailwind { version }
+    }
+
+    pub fn install(&self, destination: &str) -> Result<(), String> {
+        if let Ok(binary) = env::var(BINARY_ENV) {
+            println!("{} {}", "Using tailwind binary".blue(), binary.blue().bold());
+            return match fs::copy(&binary, destination) {
+                Ok(_) => Ok(()),
+                Err(e) => Err(binary + ": " + &e.to_string()),
+            };
+        }
+
+        let asset = Tailwind::asset_name()?;
+        let cached = self.cache_path(&asset);
+        let base = Tailwind::base_url();
+
+        match &cached {
+            Some(path) if path.is_file() => {
+                println!("{} {}", "Using cached tailwind".blue(), path.to_string_lossy().blue().bold());
+            }
+            Some(path) => self.download(&base, &asset, path)?,
+            None => return download_file(&self.url(&base, &asset), destination),
+        }
+
+        match cached {
+            Some(path) => match fs::copy(&path, destination) {
+                Ok(_) => Ok(()),
+                Err(e) => Err(e.to_string()),
+            },
+            None => Ok(()),
+        }
+    }
+
+    pub fn is_available_offline(&self) -> bool {
+        if env::var(BINARY_ENV).is_ok() {
+            return true;
+        }
+
+        match Tailwind::asset_name() {
+            Ok(asset) => self.cache_path(&asset).is_some_and(|path| path.is_file()),
+            Err(_) => false,
+        }
+    }
+
+    pub fn asset_name() -> Result<String, String> {
+        let os = match OS {
+            "linux" => "linux",
+            "macos" => "macos",
+            "windows" => "windows",
+            os => return Err("Tailwind does not publish a binary for ".to_string() + os),
+        };
+        let arch = match ARCH {
+            "x86_64" => "x64",
+            "aarch64" => "arm64",
+            "arm" => "armv7",
+            arch => return Err("Tailwind does not publish a binary for ".to_string() + arch),
+        };
+        let extension = if os == "windows" { ".exe" } else { "" };
+
+        Ok(format!("tailwindcss-{}-{}{}", os, arch, extension))
+    }
+
+    fn base_url() -> String {
+        env::var(MIRROR_ENV).unwrap_or(RELEASES_URL.to_string())
+    }
+
+    fn url(&self, base: &str, asset: &str) -> String {
+        let base = base.trim_end_matches('/');
+
+        if self.version == "latest" {
+            format!("{}/latest/download/{}", base, asset)
+        } else {
+            format!("{}/download/{}/{}", base, self.version, asset)
+        }
+    }
+
+    // latest moves to a new release without the path changing, so it is downloaded every time
+    fn cache_path(&self, asset: &str) -> Option<PathBuf> {
+        if self.version == "latest" {
+            return None;
+        }
+
+        ::dirs::cache_dir().map(|dir| {
+            dir.join("blue_eyes")
+                .join("tailwindcss")
+                .join(&self.version)
+                .join(asset)
+        })
+    }
+
+    fn download(&self, base: &str, asset: &str, path: &PathBuf) -> Result<(), String> {
+        let url = self.url(base, asset);
+        println!("{} {}", "Downloading".blue(), url.blue().bold());
+
+        if let Some(parent) = path.parent() {
+            if let Err(e) = create_dir_all(parent) {
+                return Err(e.to_string());
+            }
+        }
+
+        // download next to the cache entry so an interrupted download is never reused
+        let partial = path.with_extension("part");
+        download_file(&url, &partial.to_string_lossy())?;
+
+        match fs::rename(&partial, path) {
+            Ok(_) => Ok(()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    // Answers a single GET with body and returns the requested path.
+    fn serve_once(body: &'static str) -> (String, thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = vec![];
+            let mut buffer = [0; 1024];
+            while !request.ends_with(b"\r\n\r\n") {
+                let read = stream.read(&mut buffer).unwrap();
+                if read == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buffer[..read]);
+            }
+
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).unwrap();
+
+            let request = String::from_utf8_lossy(&request).to_string();
+            request.split_whitespace().nth(1).unwrap_or_default().to_string()
+        });
+
+        (base, handle)
+    }
+
+    #[test]
+    fn normalizes_the_version() {
+        assert_eq!(Tailwind::new("3.4.17").version, "v3.4.17");
+        assert_eq!(Tailwind::new("v3.4.17").version, "v3.4.17");
+        assert_eq!(Tailwind::new("latest").version, "latest");
+    }
+
+    #[test]
+    #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+    fn selects_the_linux_x64_asset() {
+        assert_eq!(Tailwind::asset_name().unwrap(), "tailwindcss-linux-x64");
+    }
+
+    #[test]
+    #[cfg(all(target_os = "macos", target_arch = "aarch64"))]
+    fn selects_the_macos_arm64_asset() {
+        assert_eq!(Tailwind::asset_name().unwrap(), "tailwindcss-macos-arm64");
+    }
+
+    #[test]
+    #[cfg(all(target_os = "windows", target_arch = "x86_64"))]
+    fn selects_the_windows_x64_asset() {
+        assert_eq!(Tailwind::asset_name().unwrap(), "tailwindcss-windows-x64.exe");
+    }
+
+    #[test]
+    fn builds_release_urls() {
+        let base = "https://mirror.example.com/tailwindcss/";
+
+        assert_eq!(
+            Tailwind::new("latest").url(base, "tailwindcss-linux-x64"),
+            "https://mirror.example.com/tailwindcss/latest/download/tailwindcss-linux-x64"
+        );
+        assert_eq!(
+            Tailwind::new("3.4.17").url(base, "tailwindcss-linux-x64"),
+            "https://mirror.example.com/tailwindcss/download/v3.4.17/tailwindcss-linux-x64"
+        );
+    }
+
+    #[test]
+    fn never_caches_latest() {
+        assert_eq!(Tailwind::new("latest").cache_path("tailwindcss-linux-x64"), None);
+    }
+
+    #[test]
+    fn downloads_from_the_mirror() {
+        let (base, server) = serve_once("tailwind binary");
+
+        let tailwind = Tailwind::new("v3.4.17");
+        let path = env::temp_dir()
+            .join(format!("blue_eyes_tailwind_{}", std::process::id()))
+            .join("tailwindcss-linux-x64");
+        let result = tailwind.download(&base, "tailwindcss-linux-x64", &path);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(server.join().unwrap(), "/download/v3.4.17/tailwindcss-linux-x64");
+        assert_eq!(fs::read_to_string(&path).unwrap(), "tailwind binary");
+        assert!(!path.with_extension("part").exists());
+
+        fs::remove_dir_all(path.parent().unwrap()).ok();
+    }
+}
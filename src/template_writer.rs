@@ -1,36 +1,161 @@
+use colored::Colorize;
 use rust_embed::RustEmbed;
-use std::fs::OpenOptions;
-use std::io::Write;
+use similar::{ChangeTag, TextDiff};
+use std::fs::{self, create_dir_all};
+use std::path::{Path, PathBuf};
 use std::str::from_utf8;
 use tera::{Context, Tera};
+use crate::dirs::Dir;
 
 #[derive(RustEmbed)]
 #[folder = "file_templates"]
 struct FileTemplates;
 
-pub fn write_template(output_path: String, template_name: String, context: &Context) -> Result<bool, String> {
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum WriteMode {
+    #[default]
+    Check,
+    Force,
+    Skip,
+    Pretend,
+    // checks for conflicts without writing or reporting, run before a generator touches anything
+    Preflight,
+}
+
+pub fn write_template(output_path: String, template_name: String, context: &Context, mode: WriteMode) -> Result<bool, String> {
     let file_contents = match render(template_name.as_str(), context) {
         Ok(file_contents) => file_contents,
         Err(e) => return Err(e.to_string()),
     };
 
-    match write_to_file(output_path, &file_contents) {
-        Ok(_) => Ok(true),
-        Err(e) => Err(e.to_string()),
+    write_file(&output_path, file_contents.as_bytes(), mode)
+}
+
+// Writes a generated file, refusing to replace a different existing file unless forced.
+pub fn write_file(output_path: &str, content: &[u8], mode: WriteMode) -> Result<bool, String> {
+    if mode == WriteMode::Preflight {
+        return match fs::read(output_path) {
+            Ok(existing) if existing != content => Err(conflict_message(output_path)),
+            _ => Ok(false),
+        };
+    }
+
+    let existing = match fs::read(output_path) {
+        Ok(existing) => existing,
+        Err(_) => {
+            report("create", output_path);
+            if mode == WriteMode::Pretend {
+                return Ok(false);
+            }
+            return save(output_path, content);
+        }
+    };
+
+    if existing == content {
+        report("identical", output_path);
+        return Ok(false);
+    }
+
+    match mode {
+        WriteMode::Force => {
+            report("force", output_path);
+            save(output_path, content)
+        }
+        WriteMode::Skip => {
+            report("skip", output_path);
+            Ok(false)
+        }
+        WriteMode::Pretend => {
+            report("conflict", output_path);
+            print_diff(output_path, &existing, content);
+            Ok(false)
+        }
+        WriteMode::Check | WriteMode::Preflight => {
+            report("conflict", output_path);
+            Err(conflict_message(output_path))
+        }
     }
 }
 
-fn render(filename: &str, context: &Context) -> Result<String, String> {
-    let mut tera = Tera::default();
+fn conflict_message(output_path: &str) -> String {
+    output_path.to_string() + " already exists, use --force to overwrite or --skip to keep it"
+}
+
+// Rewrites a file the generator is expected to modify in place, such as config.ru.
+pub fn update_file(output_path: &str, content: &str, mode: WriteMode) -> Result<bool, String> {
+    if mode == WriteMode::Preflight {
+        return Ok(false);
+    }
+
+    let existing = fs::read_to_string(output_path).unwrap_or_default();
+    if existing == content {
+        report("identical", output_path);
+        return Ok(false);
+    }
+
+    report("update", output_path);
+    if mode == WriteMode::Pretend {
+        print_diff(output_path, existing.as_bytes(), content.as_bytes());
+        return Ok(false);
+    }
+
+    save(output_path, content.as_bytes())
+}
+
+pub fn template_names() -> Vec<String> {
+    let mut names: Vec<String> = FileTemplates::iter().map(|name| name.to_string()).collect();
+    names.sort();
+
+    names
+}
 
-    if let Some(template_data) = FileTemplates::get(filename) {
-        let template_str =
-            from_utf8(template_data.data.as_ref()).expect("Failed to convert template to string");
+pub fn embedded_template(filename: &str) -> Option<Vec<u8>> {
+    FileTemplates::get(filename).map(|template_data| template_data.data.to_vec())
+}
+
+pub fn project_templates_dir() -> PathBuf {
+    PathBuf::from(Dir::Templates(None).path())
+}
 
-        tera.add_raw_template(filename, template_str)
-            .expect("Failed to add template to Tera");
-    } else {
-        return Err("Template not found".to_string());
+pub fn user_templates_dir() -> Option<PathBuf> {
+    ::dirs::config_dir().map(|dir| dir.join("blue_eyes").join("templates"))
+}
+
+// Project overrides win over user overrides, which win over the embedded copy.
+pub fn override_path(filename: &str) -> Option<PathBuf> {
+    let mut dirs = vec![project_templates_dir()];
+    if let Some(user_dir) = user_templates_dir() {
+        dirs.push(user_dir);
+    }
+
+    dirs.into_iter()
+        .map(|dir| dir.join(filename))
+        .find(|path| path.is_file())
+}
+
+fn load_template(filename: &str) -> Result<String, String> {
+    if let Some(path) = override_path(filename) {
+        return match fs::read_to_string(&path) {
+            Ok(template_str) => Ok(template_str),
+            Err(e) => Err(path.to_string_lossy().to_string() + ": " + &e.to_string()),
+        };
+    }
+
+    match FileTemplates::get(filename) {
+        Some(template_data) => match from_utf8(template_data.data.as_ref()) {
+            Ok(template_str) => Ok(template_str.to_string()),
+            Err(e) => Err(e.to_string()),
+        },
+        None => Err("Template not found: ".to_string() + filename),
+    }
+}
+
+pub fn render(filename: &str, context: &Context) -> Result<String, String> {
+    let mut tera = Tera::default();
+
+    let template_str = load_template(filename)?;
+    if let Err(e) = tera.add_raw_template(filename, &template_str) {
+        return Err(filename.to_string() + ": " + &e.to_string());
     }
 
     match tera.render(filename, context) {
@@ -39,18 +164,197 @@ fn render(filename: &str, context: &Context) -> Result<String, String> {
     }
 }
 
-fn write_to_file(output_path: String, content: &str) -> Result<(), String> {
-    let mut file = match OpenOptions::new()
-        .append(true)
-        .create(true)
-        .open(output_path)
-    {
-        Ok(f) => f,
-        Err(e) => return Err(e.to_string()),
-    };
+fn save(output_path: &str, content: &[u8]) -> Result<bool, String> {
+    if let Some(parent) = Path::new(output_path).parent() {
+        if let Err(e) = create_dir_all(parent) {
+            return Err(e.to_string());
+        }
+    }
 
-    match file.write_all(content.as_bytes()) {
-        Ok(_) => Ok(()),
+    match fs::write(output_path, content) {
+        Ok(_) => Ok(true),
         Err(e) => Err(e.to_string()),
     }
 }
+
+fn report(status: &str, output_path: &str) {
+    let label = format!("{:>12}", status);
+    let label = match status {
+        "create" | "update" => label.green(),
+        "identical" => label.blue(),
+        "conflict" => label.red(),
+        _ => label.yellow(),
+    };
+
+    println!("{}  {}", label.bold(), output_path);
+}
+
+fn print_diff(output_path: &str, old: &[u8], new: &[u8]) {
+    println!("--- {}", output_path);
+    println!("+++ {}", output_path);
+    for (tag, line) in diff_lines(old, new) {
+        match tag {
+            None => println!("{}", line.cyan()),
+            Some(ChangeTag::Delete) => println!("{}", line.red()),
+            Some(ChangeTag::Insert) => println!("{}", line.green()),
+            Some(ChangeTag::Equal) => println!("{}", line),
+        }
+    }
+}
+
+// Unified diff lines with the change they belong to, None for a hunk header
+fn diff_lines(old: &[u8], new: &[u8]) -> Vec<(Option<ChangeTag>, String)> {
+    let old = String::from_utf8_lossy(old);
+    let new = String::from_utf8_lossy(new);
+    let diff = TextDiff::from_lines(old.as_ref(), new.as_ref());
+
+    let mut lines = vec![];
+    for hunk in diff.unified_diff().context_radius(3).iter_hunks() {
+        lines.push((None, hunk.header().to_string()));
+        for change in hunk.iter_changes() {
+            let line = format!("{}{}", change.tag(), change.value());
+            lines.push((Some(change.tag()), line.trim_end_matches('\n').to_string()));
+        }
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    // A file path in a directory of its own, removed again when the test is done
+    struct TempFile {
+        dir: PathBuf,
+        path: String,
+    }
+
+    impl TempFile {
+        fn new(name: &str) -> Self {
+            let dir = env::temp_dir().join(format!("blue_eyes_writer_{}_{}", name, std::process::id()));
+            fs::remove_dir_all(&dir).ok();
+            let path = dir.join("app").join("file.rb").to_string_lossy().to_string();
+
+            TempFile { dir, path }
+        }
+
+        fn with(name: &str, content: &str) -> Self {
+            let file = TempFile::new(name);
+            save(&file.path, content.as_bytes()).unwrap();
+
+            file
+        }
+
+        fn read(&self) -> String {
+            fs::read_to_string(&self.path).unwrap()
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            fs::remove_dir_all(&self.dir).ok();
+        }
+    }
+
+    #[test]
+    fn creates_a_missing_file_and_its_directory() {
+        let file = TempFile::new("create");
+
+        assert_eq!(write_file(&file.path, b"new", WriteMode::Check), Ok(true));
+        assert_eq!(file.read(), "new");
+    }
+
+    #[test]
+    fn leaves_an_identical_file_alone() {
+        let file = TempFile::with("identical", "same");
+
+        for mode in [WriteMode::Check, WriteMode::Force, WriteMode::Skip, WriteMode::Pretend, WriteMode::Preflight] {
+            assert_eq!(write_file(&file.path, b"same", mode), Ok(false));
+        }
+        assert_eq!(file.read(), "same");
+    }
+
+    #[test]
+    fn refuses_to_replace_a_different_file() {
+        let file = TempFile::with("conflict", "old");
+
+        let err = write_file(&file.path, b"new", WriteMode::Check).unwrap_err();
+
+        assert!(err.contains("already exists, use --force"));
+        assert_eq!(file.read(), "old");
+    }
+
+    #[test]
+    fn force_replaces_a_different_file() {
+        let file = TempFile::with("force", "old");
+
+        assert_eq!(write_file(&file.path, b"new", WriteMode::Force), Ok(true));
+        assert_eq!(file.read(), "new");
+    }
+
+    #[test]
+    fn skip_keeps_a_different_file() {
+        let file = TempFile::with("skip", "old");
+
+        assert_eq!(write_file(&file.path, b"new", WriteMode::Skip), Ok(false));
+        assert_eq!(file.read(), "old");
+    }
+
+    #[test]
+    fn pretend_writes_nothing() {
+        let missing = TempFile::new("pretend_create");
+        assert_eq!(write_file(&missing.path, b"new", WriteMode::Pretend), Ok(false));
+        assert!(!Path::new(&missing.path).exists());
+
+        let existing = TempFile::with("pretend_conflict", "old");
+        assert_eq!(write_file(&existing.path, b"new", WriteMode::Pretend), Ok(false));
+        assert_eq!(update_file(&existing.path, "new", WriteMode::Pretend), Ok(false));
+        assert_eq!(existing.read(), "old");
+    }
+
+    #[test]
+    fn pretend_diff_shows_the_changed_lines() {
+        let lines = diff_lines(b"class Post\n  one\nend\n", b"class Post\n  two\nend\n");
+
+        assert_eq!(
+            lines,
+            vec![
+                (None, "@@ -1,3 +1,3 @@".to_string()),
+                (Some(ChangeTag::Equal), " class Post".to_string()),
+                (Some(ChangeTag::Delete), "-  one".to_string()),
+                (Some(ChangeTag::Insert), "+  two".to_string()),
+                (Some(ChangeTag::Equal), " end".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn preflight_reports_a_conflict_without_writing() {
+        let existing = TempFile::with("preflight_conflict", "old");
+        assert!(write_file(&existing.path, b"new", WriteMode::Preflight).is_err());
+        assert_eq!(existing.read(), "old");
+
+        let missing = TempFile::new("preflight_create");
+        assert_eq!(write_file(&missing.path, b"new", WriteMode::Preflight), Ok(false));
+        assert!(!Path::new(&missing.path).exists());
+    }
+
+    #[test]
+    fn update_rewrites_a_file_in_place() {
+        let file = TempFile::with("update", "run Sinatra::Application");
+
+        assert_eq!(update_file(&file.path, "use PostsController", WriteMode::Check), Ok(true));
+        assert_eq!(file.read(), "use PostsController");
+        assert_eq!(update_file(&file.path, "use PostsController", WriteMode::Check), Ok(false));
+    }
+
+    #[test]
+    fn preflight_never_updates() {
+        let file = TempFile::with("preflight_update", "old");
+
+        assert_eq!(update_file(&file.path, "new", WriteMode::Preflight), Ok(false));
+        assert_eq!(file.read(), "old");
+    }
+}
@@ -0,0 +1,78 @@
+use std::fs::{self, create_dir_all};
+use std::path::Path;
+
+// Symbolic links are copied as links, following them could copy files from outside the
+// source or loop forever on a link to a parent directory.
+pub fn copy_dir(source: &Path, destination: &Path) -> Result<(), String> {
+    if let Err(e) = create_dir_all(destination) {
+        return Err(format!("Failed to create {}: {}", destination.to_string_lossy(), e));
+    }
+
+    let entries = match fs::read_dir(source) {
+        Ok(entries) => entries,
+        Err(e) => return Err(format!("Failed to read {}: {}", source.to_string_lossy(), e)),
+    };
+
+    for entry in entries {
+        let entry = entry.map_err(|e| e.to_string())?;
+        let file_type = entry.file_type().map_err(|e| e.to_string())?;
+        let from = entry.path();
+        let to = destination.join(entry.file_name());
+
+        if file_type.is_symlink() {
+            copy_link(&from, &to)?;
+        } else if file_type.is_dir() {
+            copy_dir(&from, &to)?;
+        } else if let Err(e) = fs::copy(&from, &to) {
+            return Err(format!("Failed to copy {}: {}", from.to_string_lossy(), e));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(unix)]
+fn copy_link(from: &Path, to: &Path) -> Result<(), String> {
+    let target = match fs::read_link(from) {
+        Ok(target) => target,
+        Err(e) => return Err(format!("Failed to read link {}: {}", from.to_string_lossy(), e)),
+    };
+
+    match std::os::unix::fs::symlink(target, to) {
+        Ok(_) => Ok(()),
+        Err(e) => Err(format!("Failed to copy link {}: {}", from.to_string_lossy(), e)),
+    }
+}
+
+#[cfg(not(unix))]
+fn copy_link(from: &Path, _to: &Path) -> Result<(), String> {
+    Err(format!("Failed to copy {}: symbolic links can only be copied on unix", from.to_string_lossy()))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::os::unix::fs::symlink;
+
+    #[test]
+    fn copies_links_without_following_them() {
+        let root = env::temp_dir().join(format!("blue_eyes_fcopy_{}", std::process::id()));
+        let source = root.join("source");
+        let destination = root.join("destination");
+        fs::create_dir_all(source.join("app")).unwrap();
+        fs::write(source.join("app").join("main.rb"), "puts 1").unwrap();
+        // a link back to the parent would recurse forever if it were followed
+        symlink("..", source.join("app").join("parent")).unwrap();
+
+        let result = copy_dir(&source, &destination);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(fs::read_to_string(destination.join("app").join("main.rb")).unwrap(), "puts 1");
+        let link = destination.join("app").join("parent");
+        assert!(fs::symlink_metadata(&link).unwrap().file_type().is_symlink());
+        assert_eq!(fs::read_link(&link).unwrap(), Path::new(".."));
+
+        fs::remove_dir_all(&root).ok();
+    }
+}
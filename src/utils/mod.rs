@@ -1 +1,3 @@
 pub mod fget;
+pub mod fcopy;
+pub mod stream;
@@ -0,0 +1,18 @@
+use std::io::{BufRead, BufReader, Read};
+use std::thread::{spawn, JoinHandle};
+
+// Prints a child's output line by line as it arrives instead of buffering it until exit.
+pub fn forward<R, F>(reader: R, print: F) -> JoinHandle<()>
+where
+    R: Read + Send + 'static,
+    F: Fn(&str) + Send + 'static,
+{
+    spawn(move || {
+        for line in BufReader::new(reader).lines() {
+            match line {
+                Ok(line) => print(&line),
+                Err(e) => eprintln!("Error: {}", e),
+            }
+        }
+    })
+}